@@ -0,0 +1,346 @@
+//! Saving an unfinished `QuizSession` so it can be resumed later (see `QuizSession::save` and
+//! `QuizSession::resume`).
+//!
+//! Attempts are saved as plain text, one `key: value` line at a time:
+//!
+//! ```text
+//! quiz-app attempt
+//! quiz: 5d1a0c3e9f3b7a21
+//! seed: 1234
+//! timed-out: false
+//! practice: false
+//! hints: 1
+//! question: 2 1,0,2
+//! question: 0 inverse
+//! question: 2 1,0,2 repeat
+//! answer: 0 4500 - choices 1
+//! justification: it's the only one that fits
+//! ```
+//!
+//! `hints` is how many hints have been given for the question waiting for an answer.
+//! `question` lines are the questions in the order they're asked (the question's index in the
+//! quiz, then either the order of its answers or `inverse`, then `repeat` if it's being asked
+//! again after being missed). `answer` lines are the answers given
+//! so far, in the same order (the hints used, the milliseconds spent, any flags, then the
+//! response), each optionally followed by the quiz-taker's justification.
+
+use std::time::Duration;
+
+use crate::Response;
+
+const HEADER: &str = "quiz-app attempt";
+
+/// The state of an attempt at a quiz.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SavedAttempt {
+    /// The `Quiz::fingerprint` of the quiz the attempt is at
+    pub quiz: u64,
+    pub seed: Option<u64>,
+    /// Whether the quiz's time limit ran out
+    pub timed_out: bool,
+    /// Whether every missed question is repeated (see `QuizSession::practice`)
+    pub practice: bool,
+    /// Hints given for the current, unanswered question
+    pub hints_used: usize,
+    /// The questions in the order they're asked
+    pub questions: Vec<SavedQuestion>,
+    /// The answers given so far
+    pub answers: Vec<SavedAnswer>,
+}
+
+/// A question as it's asked in an attempt.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SavedQuestion {
+    /// Index of the question in `Quiz::questions`
+    pub index: usize,
+    /// Whether the question's inverse is asked (see the `ask_inverse` option)
+    pub inverse: bool,
+    /// Indices into the question's answers, in the order they're presented
+    pub answer_order: Vec<usize>,
+    /// Whether the question is being asked again since it was missed (see the `repeat_missed`
+    /// option)
+    pub repeat: bool,
+}
+
+/// Everything the quiz-taker did on a question, enough to replay it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SavedAnswer {
+    pub response: Response,
+    pub hints_used: usize,
+    pub time_spent: Duration,
+    pub timed_out: bool,
+    /// Whether the quiz-taker marked their answer as correct (see the `allow_review` option)
+    pub marked_correct: bool,
+    pub justification: Option<String>,
+}
+
+impl std::fmt::Display for SavedAttempt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        writeln!(f, "quiz: {0:016x}", self.quiz)?;
+        match self.seed {
+            Some(seed) => writeln!(f, "seed: {seed}")?,
+            None => writeln!(f, "seed: none")?,
+        }
+        writeln!(f, "timed-out: {0}", self.timed_out)?;
+        writeln!(f, "practice: {0}", self.practice)?;
+        writeln!(f, "hints: {0}", self.hints_used)?;
+
+        for question in &self.questions {
+            if question.inverse {
+                write!(f, "question: {0} inverse", question.index)?;
+            } else {
+                write!(f, "question: {0} {1}", question.index, join(&question.answer_order))?;
+            }
+            writeln!(f, "{0}", if question.repeat { " repeat" } else { "" })?;
+        }
+
+        for answer in &self.answers {
+            let flags: Vec<&str> = [(answer.timed_out, "timed-out"), (answer.marked_correct, "marked-correct")]
+                .into_iter()
+                .filter_map(|(set, flag)| set.then_some(flag))
+                .collect();
+
+            write!(f, "answer: {0} {1} {2} ",
+                answer.hints_used,
+                answer.time_spent.as_millis(),
+                if flags.is_empty() { "-".to_owned() } else { flags.join(",") },
+            )?;
+            match &answer.response {
+                Response::Text(text) => writeln!(f, "text {0}", escape(text))?,
+                Response::Choices(choices) => writeln!(f, "choices {0}", join(choices))?,
+            }
+
+            if let Some(justification) = &answer.justification {
+                writeln!(f, "justification: {0}", escape(justification))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for SavedAttempt {
+    type Err = ParseAttemptError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().enumerate();
+        let error = |line_num: usize, kind| ParseAttemptError { kind, lines_parsed: line_num };
+
+        match lines.next() {
+            Some((_, line)) if line.trim() == HEADER => {},
+            _ => return Err(error(0, ParseAttemptErrorKind::MissingHeader)),
+        }
+
+        let mut quiz = None;
+        let mut attempt = SavedAttempt { quiz: 0, seed: None, timed_out: false, practice: false, hints_used: 0, questions: vec![], answers: vec![] };
+
+        for (line_num, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let invalid = || error(line_num, ParseAttemptErrorKind::InvalidLine(line.to_owned()));
+            let (key, value) = line.split_once(": ").ok_or_else(invalid)?;
+
+            match key {
+                "quiz" => quiz = Some(u64::from_str_radix(value, 16).map_err(|_| invalid())?),
+                "seed" if value == "none" => attempt.seed = None,
+                "seed" => attempt.seed = Some(value.parse().map_err(|_| invalid())?),
+                "timed-out" => attempt.timed_out = value.parse().map_err(|_| invalid())?,
+                "practice" => attempt.practice = value.parse().map_err(|_| invalid())?,
+                "hints" => attempt.hints_used = value.parse().map_err(|_| invalid())?,
+                "question" => attempt.questions.push(parse_question(value).ok_or_else(invalid)?),
+                "answer" => attempt.answers.push(parse_answer(value).ok_or_else(invalid)?),
+                "justification" => {
+                    let answer = attempt.answers.last_mut().ok_or_else(invalid)?;
+                    answer.justification = Some(unescape(value));
+                },
+                _ => return Err(invalid()),
+            }
+        }
+
+        attempt.quiz = quiz.ok_or_else(|| error(s.lines().count(), ParseAttemptErrorKind::MissingQuiz))?;
+        Ok(attempt)
+    }
+}
+
+fn parse_question(s: &str) -> Option<SavedQuestion> {
+    let (s, repeat) = match s.strip_suffix(" repeat") {
+        Some(s) => (s, true),
+        None => (s, false),
+    };
+    let (index, order) = s.split_once(' ').unwrap_or((s, ""));
+    let index = index.parse().ok()?;
+
+    Some(match order {
+        "inverse" => SavedQuestion { index, inverse: true, answer_order: vec![], repeat },
+        _ => SavedQuestion { index, inverse: false, answer_order: split(order)?, repeat },
+    })
+}
+
+fn parse_answer(s: &str) -> Option<SavedAnswer> {
+    let mut parts = s.splitn(5, ' ');
+    let hints_used = parts.next()?.parse().ok()?;
+    let time_spent = Duration::from_millis(parts.next()?.parse().ok()?);
+    let flags = parts.next()?;
+
+    let response = match (parts.next()?, parts.next().unwrap_or("")) {
+        ("text", text) => Response::Text(unescape(text)),
+        ("choices", choices) => Response::Choices(split(choices)?),
+        _ => return None,
+    };
+
+    Some(SavedAnswer {
+        response,
+        hints_used,
+        time_spent,
+        timed_out: flags.split(',').any(|flag| flag == "timed-out"),
+        marked_correct: flags.split(',').any(|flag| flag == "marked-correct"),
+        justification: None,
+    })
+}
+
+fn join(indices: &[usize]) -> String {
+    indices.iter().map(usize::to_string).collect::<Vec<_>>().join(",")
+}
+
+fn split(s: &str) -> Option<Vec<usize>> {
+    s.split(',')
+        .filter(|i| !i.is_empty())
+        .map(|i| i.parse().ok())
+        .collect()
+}
+
+/// Escape backslashes and line breaks so text fits on one line.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseAttemptError {
+    kind: ParseAttemptErrorKind,
+    lines_parsed: usize,
+}
+
+impl std::fmt::Display for ParseAttemptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error on line {0}: {1}", self.lines_parsed + 1, self.kind)
+    }
+}
+
+impl std::error::Error for ParseAttemptError {}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseAttemptErrorKind {
+    MissingHeader,
+    MissingQuiz,
+    InvalidLine(String),
+}
+
+impl std::fmt::Display for ParseAttemptErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingHeader => write!(f, "not a saved attempt (expected `{HEADER}`)"),
+            Self::MissingQuiz => write!(f, "the attempt doesn't say which quiz it's for"),
+            Self::InvalidLine(line) => write!(f, "invalid line '{line}'"),
+        }
+    }
+}
+
+/// Why a `SavedAttempt` couldn't be resumed.
+#[derive(Debug, PartialEq)]
+pub enum ResumeError {
+    /// The quiz changed since the attempt was saved
+    QuizChanged,
+    /// The attempt's questions or answers don't fit the quiz
+    Mismatch,
+}
+
+impl std::fmt::Display for ResumeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::QuizChanged => write!(f, "the quiz has changed since the attempt was saved"),
+            Self::Mismatch => write!(f, "the attempt's questions don't match the quiz"),
+        }
+    }
+}
+
+impl std::error::Error for ResumeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attempt_round_trips() {
+        let attempt = SavedAttempt {
+            quiz: 0x5d1a0c3e9f3b7a21,
+            seed: Some(1234),
+            timed_out: false,
+            practice: true,
+            hints_used: 1,
+            questions: vec![
+                SavedQuestion { index: 2, inverse: false, answer_order: vec![1, 0, 2], repeat: false },
+                SavedQuestion { index: 1, inverse: false, answer_order: vec![0], repeat: false },
+                SavedQuestion { index: 0, inverse: true, answer_order: vec![], repeat: false },
+                SavedQuestion { index: 2, inverse: false, answer_order: vec![1, 0, 2], repeat: true },
+            ],
+            answers: vec![
+                SavedAnswer {
+                    response: Response::Choices(vec![1]),
+                    hints_used: 0,
+                    time_spent: Duration::from_millis(4500),
+                    timed_out: false,
+                    marked_correct: false,
+                    justification: Some("it's the only one\nthat fits".to_owned()),
+                },
+                SavedAnswer {
+                    response: Response::Text("a \\ b\n".to_owned()),
+                    hints_used: 2,
+                    time_spent: Duration::from_secs(30),
+                    timed_out: true,
+                    marked_correct: true,
+                    justification: None,
+                },
+            ],
+        };
+
+        let saved = attempt.to_string();
+        assert!(saved.contains("\npractice: true\nhints: 1\n"));
+        assert!(saved.contains("question: 0 inverse\nquestion: 2 1,0,2 repeat\n"));
+        assert!(saved.contains("answer: 2 30000 timed-out,marked-correct text a \\\\ b\\n\n"));
+        assert_eq!(saved.parse(), Ok(attempt));
+    }
+
+    #[test]
+    fn attempt_errors() {
+        assert_eq!("quiz: 12".parse::<SavedAttempt>(), Err(ParseAttemptError { kind: ParseAttemptErrorKind::MissingHeader, lines_parsed: 0 }));
+        assert_eq!("quiz-app attempt\nseed: 1".parse::<SavedAttempt>(), Err(ParseAttemptError { kind: ParseAttemptErrorKind::MissingQuiz, lines_parsed: 2 }));
+        assert_eq!(
+            "quiz-app attempt\nquiz: 12\nanswer: 0 10 - maybe 1".parse::<SavedAttempt>(),
+            Err(ParseAttemptError { kind: ParseAttemptErrorKind::InvalidLine("answer: 0 10 - maybe 1".to_owned()), lines_parsed: 2 }),
+        );
+    }
+}
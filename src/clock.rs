@@ -0,0 +1,111 @@
+//! Clocks for timing quizzes (see the `time_limit` option).
+//!
+//! A `QuizSession` reads the time from a `Clock` instead of straight from the system, so timed
+//! quizzes can be tested (and replayed) with a `ManualClock`.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// A source of time.
+pub trait Clock {
+    /// The time since some fixed point (like when the clock was created).
+    fn now(&self) -> Duration;
+}
+
+/// The system's monotonic clock.
+#[derive(Clone, Debug)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self { start: Instant::now() }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that only moves when it's told to.
+///
+/// Clones share the same time, so a test can keep a clone to `advance` a clock it gave away.
+#[derive(Clone, Debug, Default)]
+pub struct ManualClock {
+    now: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// A shared `Clock`, so the types holding one can still be `Clone`, `Debug`, and `PartialEq`
+/// (clocks are equal if they're the same clock).
+#[derive(Clone)]
+pub(crate) struct SharedClock(Rc<dyn Clock>);
+
+impl SharedClock {
+    pub(crate) fn new(clock: impl Clock + 'static) -> Self {
+        Self(Rc::new(clock))
+    }
+
+    pub(crate) fn now(&self) -> Duration {
+        self.0.now()
+    }
+}
+
+impl Default for SharedClock {
+    fn default() -> Self {
+        Self::new(SystemClock::default())
+    }
+}
+
+impl std::fmt::Debug for SharedClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SharedClock({0:?})", self.now())
+    }
+}
+
+impl PartialEq for SharedClock {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::addr_eq(Rc::as_ptr(&self.0), Rc::as_ptr(&other.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_advances() {
+        let clock = ManualClock::new();
+        let handle = clock.clone();
+        assert_eq!(clock.now(), Duration::ZERO);
+
+        handle.advance(Duration::from_secs(5));
+        assert_eq!(clock.now(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn shared_clocks_compare_by_identity() {
+        let clock = SharedClock::new(ManualClock::new());
+        assert_eq!(clock, clock.clone());
+        assert_ne!(clock, SharedClock::new(ManualClock::new()));
+    }
+}
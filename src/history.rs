@@ -0,0 +1,344 @@
+//! A history of finished attempts at quizzes, and statistics about how the quiz-taker is doing
+//! (see `quiz-app stats`).
+//!
+//! The history is a JSON Lines file (one attempt per line), appended to whenever a quiz is
+//! finished. Attempts are keyed by the quiz's path and its `Quiz::fingerprint`, so attempts at
+//! older versions of a quiz can be told apart.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::json::Json;
+use crate::results::QuizResult;
+
+/// A finished attempt at a quiz.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    /// The path of the quiz's file
+    pub quiz: String,
+    /// The `Quiz::fingerprint` of the quiz when it was taken
+    pub quiz_hash: u64,
+    /// When the attempt was finished, in seconds since the unix epoch
+    pub finished_at: u64,
+    pub result: QuizResult,
+}
+
+impl HistoryEntry {
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("quiz".to_owned(), Json::string(&self.quiz)),
+            ("hash".to_owned(), Json::string(format!("{0:016x}", self.quiz_hash))),
+            ("finished_at".to_owned(), Json::number(self.finished_at)),
+            ("result".to_owned(), self.result.to_json()),
+        ])
+    }
+
+    fn from_json(json: &Json) -> Option<Self> {
+        Some(Self {
+            quiz: json.get("quiz")?.as_str()?.to_owned(),
+            quiz_hash: u64::from_str_radix(json.get("hash")?.as_str()?, 16).ok()?,
+            finished_at: json.get("finished_at")?.as_number()?,
+            result: QuizResult::from_json(json.get("result")?)?,
+        })
+    }
+}
+
+/// Where the history is kept: `$XDG_DATA_HOME/quiz-app/history.jsonl`, falling back to
+/// `~/.local/share` (or `%APPDATA%` on Windows) if `XDG_DATA_HOME` isn't set.
+pub fn default_path() -> Option<PathBuf> {
+    Some(data_dir()?.join("history.jsonl"))
+}
+
+/// The directory the app keeps its data in (see `default_path`).
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    let var = |name| std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+
+    let data_dir = var("XDG_DATA_HOME")
+        .or_else(|| var("HOME").map(|home| home.join(".local").join("share")))
+        .or_else(|| var("APPDATA"))?;
+
+    Some(data_dir.join("quiz-app"))
+}
+
+/// Add a finished attempt to the history at `path` (creating it if it doesn't exist).
+pub fn append(path: &Path, entry: &HistoryEntry) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut history = fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(history, "{0}", entry.to_json())
+}
+
+/// Read every attempt in the history at `path` (there are none if it doesn't exist yet).
+///
+/// Lines that can't be read (like ones cut short by a crash) are skipped.
+pub fn read(path: &Path) -> io::Result<Vec<HistoryEntry>> {
+    let history = match fs::read_to_string(path) {
+        Ok(history) => history,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+
+    Ok(history.lines()
+        .filter_map(|line| line.parse().ok())
+        .filter_map(|json| HistoryEntry::from_json(&json))
+        .collect())
+}
+
+/// How the quiz-taker has been doing on a quiz.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    /// Every attempt, oldest first
+    attempts: Vec<Attempt>,
+    /// Questions of the current version of the quiz that were missed, most missed first
+    most_missed: Vec<Missed>,
+    /// Average time spent on each question in the current version of the quiz
+    average_time: Option<Duration>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Attempt {
+    finished_at: u64,
+    score: f32,
+    total_score: f32,
+    /// Whether the attempt was at an older version of the quiz
+    outdated: bool,
+}
+
+impl Attempt {
+    fn percent(&self) -> f32 {
+        if self.total_score == 0.0 { 0.0 } else { self.score * 100.0 / self.total_score }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Missed {
+    title: String,
+    missed: usize,
+    asked: usize,
+}
+
+/// How many of the most missed questions are shown.
+const MOST_MISSED: usize = 5;
+
+impl Stats {
+    /// Statistics for the attempts in `entries` (which should all be at the same quiz), where the
+    /// quiz's current `Quiz::fingerprint` is `quiz_hash`.
+    ///
+    /// Only attempts at the current version of the quiz count towards the most-missed questions
+    /// and the average time (questions may have changed since older ones).
+    pub fn new(entries: &[HistoryEntry], quiz_hash: u64) -> Self {
+        let mut entries: Vec<&HistoryEntry> = entries.iter().collect();
+        entries.sort_by_key(|entry| entry.finished_at);
+
+        let attempts = entries.iter()
+            .map(|entry| Attempt {
+                finished_at: entry.finished_at,
+                score: entry.result.score,
+                total_score: entry.result.total_score,
+                outdated: entry.quiz_hash != quiz_hash,
+            })
+            .collect();
+
+        let current = || entries.iter()
+            .filter(|entry| entry.quiz_hash == quiz_hash)
+            .flat_map(|entry| &entry.result.questions);
+
+        // questions are keyed by their index (and whether they're inverses) since titles can repeat
+        let mut missed: Vec<((usize, bool), Missed)> = vec![];
+        for question in current() {
+            let key = (question.index, question.inverse);
+            let i = match missed.iter().position(|(k, _)| *k == key) {
+                Some(i) => i,
+                None => {
+                    missed.push((key, Missed { title: question.title.clone(), missed: 0, asked: 0 }));
+                    missed.len() - 1
+                },
+            };
+
+            missed[i].1.asked += 1;
+            if !question.is_correct {
+                missed[i].1.missed += 1;
+            }
+        }
+
+        let mut most_missed: Vec<Missed> = missed.into_iter()
+            .map(|(_, missed)| missed)
+            .filter(|missed| missed.missed > 0)
+            .collect();
+        // most often missed first (stable, so ties stay in the order they were asked)
+        most_missed.sort_by(|a, b| (b.missed * a.asked).cmp(&(a.missed * b.asked)).then(b.missed.cmp(&a.missed)));
+        most_missed.truncate(MOST_MISSED);
+
+        let questions = current().count();
+        let average_time = (questions > 0).then(|| current().map(|question| question.time_spent).sum::<Duration>() / questions as u32);
+
+        Self { attempts, most_missed, average_time }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attempts.is_empty()
+    }
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (Some(first), Some(last)) = (self.attempts.first(), self.attempts.last()) else {
+            return writeln!(f, "No finished attempts at this quiz yet.");
+        };
+
+        writeln!(f, "Attempts: {0}", self.attempts.len())?;
+        writeln!(f, "\nScores (oldest first):")?;
+        for attempt in &self.attempts {
+            writeln!(f, "    {0}  {1}/{2} ({3:.0}%){4}",
+                format_date(attempt.finished_at),
+                crate::format_points(attempt.score),
+                crate::format_points(attempt.total_score),
+                attempt.percent(),
+                if attempt.outdated { "  (older version of the quiz)" } else { "" },
+            )?;
+        }
+
+        let percents = || self.attempts.iter().map(Attempt::percent);
+        let best = percents().fold(f32::MIN, f32::max);
+        let average = percents().sum::<f32>() / self.attempts.len() as f32;
+        writeln!(f, "Best: {best:.0}%, average: {average:.0}%")?;
+
+        if self.attempts.len() > 1 {
+            match last.percent() - first.percent() {
+                change if change.round() > 0.0 => writeln!(f, "Trend: up {change:.0}% since the first attempt")?,
+                change if change.round() < 0.0 => writeln!(f, "Trend: down {0:.0}% since the first attempt", -change)?,
+                _ => writeln!(f, "Trend: no change since the first attempt")?,
+            }
+        }
+
+        if !self.most_missed.is_empty() {
+            writeln!(f, "\nMost missed questions:")?;
+            for missed in &self.most_missed {
+                writeln!(f, "    missed {0} of {1} time(s): {2}", missed.missed, missed.asked, missed.title)?;
+            }
+        }
+
+        if let Some(average_time) = self.average_time {
+            writeln!(f, "\nAverage time per question: {0:.1}s", average_time.as_secs_f64())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Format a unix time as a UTC date and time (ex. `2024-03-09 14:05 UTC`).
+fn format_date(unix_secs: u64) -> String {
+    let (days, secs) = (unix_secs / 86400, unix_secs % 86400);
+
+    // days since the epoch to a civil date (see http://howardhinnant.github.io/date_algorithms.html)
+    let z = days + 719468;
+    let era = z / 146097;
+    let day_of_era = z % 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!("{year}-{month:02}-{day:02} {0:02}:{1:02} UTC", secs / 3600, secs / 60 % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Metadata;
+    use crate::results::QuestionResult;
+
+    fn entry(finished_at: u64, quiz_hash: u64, correct: &[bool]) -> HistoryEntry {
+        let questions: Vec<QuestionResult> = correct.iter().enumerate()
+            .map(|(i, &is_correct)| QuestionResult {
+                index: i,
+                title: format!("question {i}"),
+                inverse: false,
+                answer: "answer".to_owned(),
+                correct_answers: vec!["answer".to_owned()],
+                points: if is_correct { 1.0 } else { 0.0 },
+                value: 1.0,
+                is_correct,
+                skipped: false,
+                timed_out: false,
+                time_spent: Duration::from_secs(i as u64 + 1),
+                hints_used: 0,
+            })
+            .collect();
+
+        HistoryEntry {
+            quiz: "/quizzes/rocks.qz".to_owned(),
+            quiz_hash,
+            finished_at,
+            result: QuizResult {
+                metadata: Metadata::default(),
+                seed: None,
+                score: questions.iter().map(|question| question.points).sum(),
+                total_score: questions.len() as f32,
+                timed_out: false,
+                questions,
+            },
+        }
+    }
+
+    #[test]
+    fn history_round_trips() {
+        let dir = std::env::temp_dir().join(format!("quiz-app-history-test-{0}", std::process::id()));
+        let path = dir.join("history.jsonl");
+
+        let entries = [entry(1_700_000_000, 1, &[true, false]), entry(1_700_086_400, 2, &[true, true])];
+        for entry in &entries {
+            append(&path, entry).expect("history should be written");
+        }
+        fs::OpenOptions::new().append(true).open(&path).and_then(|mut file| writeln!(file, "{{\"cut short"))
+            .expect("history should be written");
+
+        assert_eq!(read(&path).expect("history should be read"), entries);
+        assert_eq!(read(&dir.join("missing.jsonl")).expect("missing history is empty"), []);
+
+        fs::remove_dir_all(dir).expect("test history should be removed");
+    }
+
+    #[test]
+    fn stats() {
+        let entries = [
+            entry(1_700_086_400, 2, &[true, false, false]),
+            entry(1_700_000_000, 1, &[false, false]),
+            entry(1_700_172_800, 2, &[true, true, false]),
+        ];
+
+        let stats = Stats::new(&entries, 2).to_string();
+        assert_eq!(stats, "\
+            Attempts: 3\n\
+            \n\
+            Scores (oldest first):\n    \
+                2023-11-14 22:13 UTC  0/2 (0%)  (older version of the quiz)\n    \
+                2023-11-15 22:13 UTC  1/3 (33%)\n    \
+                2023-11-16 22:13 UTC  2/3 (67%)\n\
+            Best: 67%, average: 33%\n\
+            Trend: up 67% since the first attempt\n\
+            \n\
+            Most missed questions:\n    \
+                missed 2 of 2 time(s): question 2\n    \
+                missed 1 of 2 time(s): question 1\n\
+            \n\
+            Average time per question: 2.0s\n\
+        ");
+
+        assert!(Stats::new(&[], 2).is_empty());
+        assert_eq!(Stats::new(&[], 2).to_string(), "No finished attempts at this quiz yet.\n");
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(format_date(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_date(951_782_400), "2000-02-29 00:00 UTC");
+        assert_eq!(format_date(1_710_000_000), "2024-03-09 16:00 UTC");
+    }
+}
@@ -0,0 +1,361 @@
+//! Just enough JSON to write results and read back the attempt history.
+
+use std::fmt::Write;
+
+/// A JSON value.
+///
+/// Numbers keep the text they were written with, so they round-trip exactly (`f32`s included).
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    /// Keys in the order they were written
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub(crate) fn number(n: impl std::fmt::Display) -> Self {
+        Self::Number(n.to_string())
+    }
+
+    pub(crate) fn string(s: impl Into<String>) -> Self {
+        Self::String(s.into())
+    }
+
+    pub(crate) fn option(s: Option<&str>) -> Self {
+        s.map_or(Self::Null, Self::string)
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Self::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// The number, parsed as whatever type is asked for.
+    pub(crate) fn as_number<T: std::str::FromStr>(&self) -> Option<T> {
+        match self {
+            Self::Number(n) => n.parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The string, or `None` for `null`.
+    pub(crate) fn as_option(&self) -> Option<Option<&str>> {
+        match self {
+            Self::Null => Some(None),
+            _ => self.as_str().map(Some),
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Self::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+/// Written compactly, on one line, or pretty-printed with `{:#}` (every array item and object
+/// entry on its own line, indented by two spaces per level).
+impl std::fmt::Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return self.write_pretty(f, 0);
+        }
+
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::String(s) => write!(f, "{0}", quote(s)),
+            Self::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    write!(f, "{0}{item}", if i == 0 { "" } else { ", " })?;
+                }
+                write!(f, "]")
+            },
+            Self::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    write!(f, "{0}{1}: {value}", if i == 0 { "" } else { ", " }, quote(key))?;
+                }
+                write!(f, "}}")
+            },
+        }
+    }
+}
+
+impl Json {
+    fn write_pretty(&self, f: &mut std::fmt::Formatter<'_>, indent: usize) -> std::fmt::Result {
+        let pad = "  ".repeat(indent + 1);
+
+        match self {
+            Self::Array(items) if !items.is_empty() => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    write!(f, "{0}\n{pad}", if i == 0 { "" } else { "," })?;
+                    item.write_pretty(f, indent + 1)?;
+                }
+                write!(f, "\n{0}]", "  ".repeat(indent))
+            },
+            Self::Object(entries) if !entries.is_empty() => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    write!(f, "{0}\n{pad}{1}: ", if i == 0 { "" } else { "," }, quote(key))?;
+                    value.write_pretty(f, indent + 1)?;
+                }
+                write!(f, "\n{0}}}", "  ".repeat(indent))
+            },
+            value => write!(f, "{value}"),
+        }
+    }
+}
+
+impl std::str::FromStr for Json {
+    type Err = ParseJsonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { s, pos: 0 };
+        let value = parser.value()?;
+
+        parser.skip_whitespace();
+        if parser.pos == s.len() { Ok(value) } else { Err(parser.error()) }
+    }
+}
+
+/// Quote and escape a string.
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => _ = write!(quoted, "\\u{0:04x}", c as u32),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseJsonError {
+    /// Byte offset of the error
+    pos: usize,
+}
+
+impl std::fmt::Display for ParseJsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid JSON at byte {0}", self.pos)
+    }
+}
+
+impl std::error::Error for ParseJsonError {}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self) -> ParseJsonError {
+        ParseJsonError { pos: self.pos }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.s.len() - self.rest().trim_start().len();
+    }
+
+    /// Skip `token` (after any whitespace) if it's next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), ParseJsonError> {
+        if self.eat(token) { Ok(()) } else { Err(self.error()) }
+    }
+
+    fn value(&mut self) -> Result<Json, ParseJsonError> {
+        self.skip_whitespace();
+
+        match self.rest().chars().next() {
+            _ if self.eat("null") => Ok(Json::Null),
+            _ if self.eat("true") => Ok(Json::Bool(true)),
+            _ if self.eat("false") => Ok(Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some('[') => self.array(),
+            Some('{') => self.object(),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, ParseJsonError> {
+        let len = self.rest()
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+            .unwrap_or(self.rest().len());
+        let number = &self.rest()[..len];
+
+        if number.parse::<f64>().is_err() {
+            return Err(self.error());
+        }
+
+        self.pos += len;
+        Ok(Json::number(number))
+    }
+
+    fn string(&mut self) -> Result<String, ParseJsonError> {
+        self.expect("\"")?;
+        let mut string = String::new();
+        let mut chars = self.rest().char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(string);
+                },
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => string.push('\n'),
+                    Some('r') => string.push('\r'),
+                    Some('t') => string.push('\t'),
+                    Some('b') => string.push('\u{8}'),
+                    Some('f') => string.push('\u{c}'),
+                    Some('u') => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        let c = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                        string.push(c.unwrap_or(char::REPLACEMENT_CHARACTER));
+                    },
+                    Some(c) => string.push(c),
+                    None => break,
+                },
+                c => string.push(c),
+            }
+        }
+
+        self.pos = self.s.len();
+        Err(self.error())
+    }
+
+    fn array(&mut self) -> Result<Json, ParseJsonError> {
+        self.expect("[")?;
+        let mut items = vec![];
+
+        if self.eat("]") {
+            return Ok(Json::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+
+            if self.eat("]") {
+                return Ok(Json::Array(items));
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, ParseJsonError> {
+        self.expect("{")?;
+        let mut entries = vec![];
+
+        if self.eat("}") {
+            return Ok(Json::Object(entries));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(":")?;
+            entries.push((key, self.value()?));
+
+            if self.eat("}") {
+                return Ok(Json::Object(entries));
+            }
+            self.expect(",")?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trips() {
+        let value = Json::Object(vec![
+            ("name".to_owned(), Json::string("a \"quoted\"\nline\u{1}")),
+            ("score".to_owned(), Json::number(2.5f32)),
+            ("tags".to_owned(), Json::Array(vec![Json::string("rocks"), Json::Null, Json::Bool(false)])),
+            ("empty".to_owned(), Json::Object(vec![])),
+        ]);
+
+        let written = value.to_string();
+        assert_eq!(written, r#"{"name": "a \"quoted\"\nline\u0001", "score": 2.5, "tags": ["rocks", null, false], "empty": {}}"#);
+        assert_eq!(written.parse(), Ok(value.clone()));
+
+        assert_eq!(value.get("score").and_then(Json::as_number), Some(2.5f32));
+        assert_eq!(value.get("tags").and_then(Json::as_array).map(<[Json]>::len), Some(3));
+    }
+
+    #[test]
+    fn json_pretty() {
+        let value = Json::Object(vec![
+            ("a".to_owned(), Json::Array(vec![Json::number(1), Json::Object(vec![("b".to_owned(), Json::Null)])])),
+            ("empty".to_owned(), Json::Array(vec![])),
+        ]);
+
+        assert_eq!(format!("{value:#}"), "{\n  \"a\": [\n    1,\n    {\n      \"b\": null\n    }\n  ],\n  \"empty\": []\n}");
+        assert_eq!(format!("{value:#}").parse::<Json>(), Ok(value));
+    }
+
+    #[test]
+    fn json_parses_whitespace_and_escapes() {
+        let value: Json = " { \"a\" : [ 1 , -2e3 ] , \"b\":\"\\u00e9\\t\" } ".parse().expect("json should parse");
+        assert_eq!(value.get("a"), Some(&Json::Array(vec![Json::number(1), Json::number("-2e3")])));
+        assert_eq!(value.get("b").and_then(Json::as_str), Some("é\t"));
+    }
+
+    #[test]
+    fn json_errors() {
+        assert_eq!("{\"a\": }".parse::<Json>(), Err(ParseJsonError { pos: 6 }));
+        assert_eq!("[1, 2".parse::<Json>(), Err(ParseJsonError { pos: 5 }));
+        assert_eq!("\"unterminated".parse::<Json>(), Err(ParseJsonError { pos: 13 }));
+        assert_eq!("1 2".parse::<Json>(), Err(ParseJsonError { pos: 2 }));
+    }
+}
@@ -1,24 +1,36 @@
-use std::io::{self, Write, stdin, stdout};
+use std::io::{self, BufRead, Write, stdin, stdout};
 
 use std::cmp;
-
-use std::hash::{BuildHasher, Hasher, RandomState};
-
-/* consider doing something like this
-enum ConfigValue {
-    F32(String),
-    Bool(String),
-}
-impl ConfigValue {
-    fn parse
-}
-*/
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::time::Duration;
+
+pub mod attempt;
+pub mod clock;
+pub mod fuzzy;
+pub mod history;
+mod json;
+pub mod numeric;
+pub mod options;
+pub mod regex;
+pub mod results;
+pub mod rng;
+mod session;
+pub mod study;
+use numeric::{NumericAnswer, ParseNumericAnswerError, Tolerance};
+use attempt::SavedAttempt;
+use results::{QuestionResult, QuizResult};
+use options::{ParseDurationError, Scope, config_options};
+use regex::{ParseRegexError, Regex};
+pub use session::{AskedQuestion, Feedback, Mastery, QuestionKind, QuizSession, Response, ReviewEntry, ReviewKind, SectionScore};
 
 #[derive(Debug, PartialEq)]
 pub enum ConfigValueParseError {
     ParseIntError(std::num::ParseIntError),
     ParseFloatError(std::num::ParseFloatError),
     ParseBoolError(std::str::ParseBoolError),
+    ParseEnumError(ParseEnumError),
+    ParseDurationError(ParseDurationError),
 }
 impl std::fmt::Display for ConfigValueParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -26,6 +38,8 @@ impl std::fmt::Display for ConfigValueParseError {
             Self::ParseIntError(e) => write!(f, "{e}"),
             Self::ParseFloatError(e) => write!(f, "{e}"),
             Self::ParseBoolError(e) => write!(f, "{e}"),
+            Self::ParseEnumError(e) => write!(f, "{e}"),
+            Self::ParseDurationError(e) => write!(f, "{e}"),
         }
     }
 }
@@ -36,6 +50,8 @@ impl std::error::Error for ConfigValueParseError {
             Self::ParseFloatError(e) => e,
             Self::ParseIntError(e) => e,
             Self::ParseBoolError(e) => e,
+            Self::ParseEnumError(e) => e,
+            Self::ParseDurationError(e) => e,
         })
     }
 }
@@ -55,6 +71,71 @@ impl From<std::str::ParseBoolError> for ConfigValueParseError {
         Self::ParseBoolError(value)
     }
 }
+impl From<ParseEnumError> for ConfigValueParseError {
+    fn from(value: ParseEnumError) -> Self {
+        Self::ParseEnumError(value)
+    }
+}
+impl From<ParseDurationError> for ConfigValueParseError {
+    fn from(value: ParseDurationError) -> Self {
+        Self::ParseDurationError(value)
+    }
+}
+
+/// An error parsing a value that must be one of a fixed set of names
+#[derive(Debug, PartialEq)]
+pub struct ParseEnumError {
+    /// the accepted names
+    expected: &'static [&'static str],
+}
+
+impl std::fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected one of: {0}", self.expected.join(", "))
+    }
+}
+
+impl std::error::Error for ParseEnumError {}
+
+/// How multiple-answer questions are graded
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scoring {
+    /// Full points for selecting exactly the correct answers, nothing otherwise
+    AllOrNothing,
+    /// Each option is worth an equal part of the points, earned by selecting it if it's correct
+    /// or leaving it unselected if it's incorrect (nothing at all unless a correct one is selected)
+    Proportional,
+    /// Each correct answer selected is worth an equal part of the points and each incorrect answer
+    /// selected takes a part away (never going below zero)
+    RightMinusWrong,
+}
+
+impl Scoring {
+    const NAMES: &'static [&'static str] = &["all-or-nothing", "proportional", "right-minus-wrong"];
+}
+
+impl std::str::FromStr for Scoring {
+    type Err = ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // allow the same name clarification characters as option names
+        match &s.replace(['-','_',' '], "").to_lowercase()[..] {
+            "allornothing" => Ok(Self::AllOrNothing),
+            "proportional" => Ok(Self::Proportional),
+            "rightminuswrong" => Ok(Self::RightMinusWrong),
+            _ => Err(ParseEnumError { expected: Self::NAMES }),
+        }
+    }
+}
+
+impl std::fmt::Display for Scoring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{0}", match self {
+            Self::AllOrNothing => Self::NAMES[0],
+            Self::Proportional => Self::NAMES[1],
+            Self::RightMinusWrong => Self::NAMES[2],
+        })
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub struct ConfigError {
@@ -91,6 +172,12 @@ pub enum ConfigErrorKind {
     InvalidValue(ConfigValueParseError),
     /// The comment/config is missing an appropriate delimiter (`;`, `:`, or `#`)
     MissingDelimiter,
+    /// The option can only be set in the file-level config, but it was set on a question
+    FileOnlyOption,
+    /// The option can't be set on a section (see `Section`)
+    NotForSections,
+    /// `sample` was set on a question that isn't in a `pool`
+    SampleWithoutPool,
 }
 
 impl std::fmt::Display for ConfigErrorKind {
@@ -98,6 +185,9 @@ impl std::fmt::Display for ConfigErrorKind {
         match self {
             Self::InvalidOption => write!(f, "invalid config option"),
             Self::MissingDelimiter => write!(f, "missing `;` delimiter"),
+            Self::FileOnlyOption => write!(f, "option can only be set in the file-level config"),
+            Self::NotForSections => write!(f, "option can't be set on a section"),
+            Self::SampleWithoutPool => write!(f, "`sample` can only be set on a question in a `pool`"),
             Self::InvalidValue(e) => write!(f, "{e}"),
         }
     }
@@ -109,114 +199,217 @@ impl From<ConfigValueParseError> for ConfigErrorKind {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Config {
-    value: f32,
-    case_sensitive: bool,
-    ordered: bool,
-    ordered_answers: bool,
-    tutorial: bool,
-    show_answer: bool,
-    show_value: bool,
-    show_quiz_info: bool,
+config_options! {
+    value: f32 = 1.0 => "value", Question,
+        "The point value of the question.";
+    case_sensitive: bool = false => "case-sensitive", Question,
+        "Whether typed answers must match the answer's case.";
+    ordered: bool = true => "ordered", Question,
+        "Whether the question is asked in the order written (or shuffled if false).";
+    ordered_answers: bool = true => "ordered-answers", Question,
+        "Whether the question's answers are presented in the order written (or shuffled if false).";
+    tutorial: bool = true => "tutorial", File,
+        "Whether the quiz starts with a tutorial.";
+    show_answer: bool = true => "show-answer", Question,
+        "Whether the correct answers are revealed after answering.";
+    show_value: bool = true => "show-value", Question,
+        "Whether the question's point value is shown.";
+    show_quiz_info: bool = true => "show-quiz-info", File,
+        "Whether the quiz starts with its total points, question count, and config.";
+    penalty: f32 = 0.0 => "penalty", Question,
+        "Points taken away for an incorrect (but not skipped) answer.";
+    scoring: Scoring = Scoring::AllOrNothing => "scoring", Question,
+        "How multiple-answer questions are graded.";
+    numeric_tolerance: Tolerance = Tolerance::default() => "numeric-tolerance", Question,
+        "How far off a numeric answer can be, for answers without their own tolerance.";
+    fuzzy: usize = 0 => "fuzzy", Question,
+        "How many typos a typed answer can have and still be correct.";
+    normalize: bool = false => "normalize", Question,
+        "Whether typed answers ignore accents, punctuation, and extra spaces.";
+    typed: bool = false => "typed", Question,
+        "Whether the question is typed-answer even with several answers.";
+    show_explanation: bool = false => "show-explanation", Question,
+        "Whether explanations are shown even if `show-answer` is false.";
+    hint_penalty: f32 = 0.0 => "hint-penalty", Question,
+        "The fraction of the question's value lost for each hint used.";
+    justified: bool = false => "justified", Question,
+        "Whether the quiz-taker justifies their answer (logged for review).";
+    ask_inverse: bool = false => "ask-inverse", Question,
+        "Whether the question is asked again in reverse at the end of the quiz.";
+    allow_review: bool = false => "allow-review", Question,
+        "Whether a missed answer can be marked correct by the quiz-taker (logged for review).";
+    repeat_missed: bool = false => "repeat-missed", Question,
+        "Whether a missed question is asked again later in the quiz until it's mastered.";
+    mastery: usize = 2 => "mastery", Question,
+        "How many correct answers in a row it takes to master a missed question.";
+    pool: Option<String> = None => "pool", Question,
+        "The pool the question is drawn from (see `sample`).";
+    sample: Option<usize> = None => "sample", NotInherited,
+        "How many random questions are asked from the whole quiz (file-level), a section, or the question's pool.";
+    time_limit: Option<Duration> = None => "time-limit", NotInherited,
+        "How long the whole quiz (file-level) or a single question has before it's submitted.";
+    seed: Option<u64> = None => "seed", File,
+        "The seed used to shuffle questions and answers.";
+    title: Option<String> = None => "title", File,
+        "The quiz's title.";
+    author: Option<String> = None => "author", File,
+        "Who wrote the quiz.";
+    version: Option<String> = None => "version", File,
+        "The quiz's version, for telling revisions apart.";
+    description: Option<String> = None => "description", File,
+        "What the quiz is about.";
+    tags: Option<String> = None => "tags", File,
+        "Comma-separated tags for finding the quiz in a library.";
 }
-impl std::default::Default for Config {
-    fn default() -> Self {
-        Self {
-            value: 1.0,
-            case_sensitive: false,
-            ordered: true,
-            ordered_answers: true,
-            tutorial: true,
-            show_answer: true,
-            show_value: true,
-            show_quiz_info: true,
+
+impl Config {
+    fn parse_str(base_config: &Config, config_str: &str) -> Result<Self, ConfigError> {
+        let (config, mut errors) = Self::parse_str_all(base_config, config_str);
+
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(errors.swap_remove(0))
         }
     }
-}
 
-impl Config {
-    /// Parse a `String` into a value for use in a `Config`.
+    /// Parse every line of `config_str`, collecting the errors instead of stopping at the first
+    /// one.
     ///
-    /// Desired type must have a string-parsing error that implements
-    /// `Into<ConfigValueParseError>`.
-    fn parse_val<T> (value: String, lines_parsed: usize) -> Result<T, ConfigError>
-    where 
-        // require that the desired type can parse from a string and its parse error can convert
-        // into ours
-        T: std::str::FromStr<Err: Into<ConfigValueParseError>>
-    {
-        // parse the value into the desired type and convert any errors into our parse errors
-        let parsed = value.parse().map_err(|e| Into::<ConfigValueParseError>::into(e));
-
-        // convert parse errors into fully-fledged ConfigErrors, passthrough values
-        match parsed {
-            Err(e) => return Err(ConfigError{
-                kind: ConfigErrorKind::from(e),
-                context: value,
-                lines_parsed,
-            }),
-            Ok(v) => Ok(v),
+    /// Lines that fail to parse leave the config untouched, so the returned `Config` holds every
+    /// option that *did* parse.
+    fn parse_str_all(base_config: &Config, config_str: &str) -> (Self, Vec<ConfigError>) {
+        Self::parse_str_all_in(Scope::File, base_config, config_str)
+    }
+
+    /// The config questions start from: `self`, without the options questions don't inherit.
+    fn question_base(&self) -> Config {
+        let mut base = self.clone();
+
+        for option in Config::OPTIONS.iter().filter(|option| option.scope == Scope::NotInherited) {
+            (option.set)(&mut base, None).expect("resetting an option to its default can't fail");
         }
+
+        base
     }
 
-    fn parse_str(base_config: &Config, config_str: &str) -> Result<Self, ConfigError> {
+    /// Like `parse_str_all`, but options that can't be set in `scope` are errors.
+    fn parse_str_all_in(scope: Scope, base_config: &Config, config_str: &str) -> (Self, Vec<ConfigError>) {
         let mut config = base_config.clone();
+        let mut errors = Vec::new();
 
         for (line_num, cfg) in config_str.lines().map(|l| l.trim()).enumerate() {
-            if cfg.starts_with('#') || cfg.is_empty() { continue; } // skip comments and blanks
-            
-            if !cfg.starts_with(';') { return Err(ConfigError{
-                kind: ConfigErrorKind::MissingDelimiter,
-                lines_parsed: line_num,
-                context: cfg.to_owned(),
-            })}
+            if let Err(e) = config.parse_line(scope, line_num, cfg) {
+                errors.push(e);
+            }
+        }
 
-            let (name, value) = match cfg.split_once(':') {
-                Some(t) => t,
-                None => (&cfg[..], ""), // just the name comes out (could be used to reset to
-                // default)
-            };
+        (config, errors)
+    }
 
-            // filter out `;`, trim, and replace acceptable name clarification characters
-            // (exampleName == example_name == example-name == example name)
-            let name = name[1..].trim().replace(['-','_',' '], "").to_lowercase();
-            let value = value.trim().to_lowercase();
-
-            match &name[..] {
- //                "value" => config.value = match value.parse() {
- //                    Err(e) => return Err(ConfigError{
- //                        kind: ConfigErrorKind::from(e),
- //                        context: value,
- //                        lines_parsed: line_num,
- //                    }),
- //                    Ok(v) => v,
- //                },
-
-                // f32 options
-                "value" => config.value = Self::parse_val(value, line_num)?,
-
-                // boolean options
-                "casesensitive" => config.case_sensitive = Self::parse_val(value, line_num)?,
-                "ordered" => config.ordered = Self::parse_val(value, line_num)?,
-                "orderedanswers" => config.ordered_answers = Self::parse_val(value, line_num)?,
-                "tutorial" => config.tutorial = Self::parse_val(value, line_num)?,
-                "showanswer" => config.show_answer = Self::parse_val(value, line_num)?,
-                "showvalue" => config.show_value = Self::parse_val(value, line_num)?,
-                "showquizinfo" => config.show_quiz_info = Self::parse_val(value, line_num)?,
-
-                // invalid options
-                _ => return Err(ConfigError { 
-                    kind: ConfigErrorKind::InvalidOption,
-                    lines_parsed: line_num,
-                    context: name,
-                }),
-            };
+    /// Index of the last line in `config_str` that sets the option `name` (or 0 if none do).
+    fn line_setting(config_str: &str, name: &str) -> usize {
+        let sets_option = |line: &str| line.trim()
+            .strip_prefix(';')
+            .and_then(|line| options::find(line.split(':').next().unwrap_or_default()))
+            .is_some_and(|option| option.name == name);
+
+        config_str.lines().enumerate()
+            .filter(|(_, line)| sets_option(line))
+            .map(|(i, _)| i)
+            .last()
+            .unwrap_or_default()
+    }
+
+    /// Apply a single (trimmed) line of config to `self`.
+    fn parse_line(&mut self, scope: Scope, line_num: usize, cfg: &str) -> Result<(), ConfigError> {
+        if cfg.starts_with('#') || cfg.is_empty() { return Ok(()); } // skip comments and blanks
+
+        if !cfg.starts_with(';') { return Err(ConfigError{
+            kind: ConfigErrorKind::MissingDelimiter,
+            lines_parsed: line_num,
+            context: cfg.to_owned(),
+        })}
+
+        let (name, value) = match cfg.split_once(':') {
+            Some((name, value)) => (name, Some(value.trim())),
+            None => (cfg, None), // just the name resets the option to its default
         };
 
-        Ok(config)
+        // filter out `;`, trim, and replace acceptable name clarification characters
+        // (exampleName == example_name == example-name == example name)
+        let name = options::normalize_name(name[1..].trim());
+
+        // reset every option (to the defaults, *not* to the base config)
+        if name == "reset" && value.is_none() {
+            *self = Config::default();
+            return Ok(());
+        }
+
+        let Some(option) = options::find(&name) else {
+            return Err(ConfigError {
+                kind: ConfigErrorKind::InvalidOption,
+                lines_parsed: line_num,
+                context: name,
+            });
+        };
+
+        if option.scope == Scope::File && scope == Scope::Question {
+            return Err(ConfigError {
+                kind: ConfigErrorKind::FileOnlyOption,
+                lines_parsed: line_num,
+                context: option.name.to_owned(),
+            });
+        }
+
+        (option.set)(self, value).map_err(|e| ConfigError {
+            kind: ConfigErrorKind::InvalidValue(e),
+            lines_parsed: line_num,
+            context: value.unwrap_or_default().to_owned(),
+        })
     }
 }
+impl Config {
+    pub fn value(&self) -> f32 { self.value }
+    pub fn case_sensitive(&self) -> bool { self.case_sensitive }
+    pub fn ordered(&self) -> bool { self.ordered }
+    pub fn ordered_answers(&self) -> bool { self.ordered_answers }
+    pub fn tutorial(&self) -> bool { self.tutorial }
+    pub fn show_answer(&self) -> bool { self.show_answer }
+    pub fn show_value(&self) -> bool { self.show_value }
+    pub fn show_quiz_info(&self) -> bool { self.show_quiz_info }
+    pub fn penalty(&self) -> f32 { self.penalty }
+    pub fn scoring(&self) -> Scoring { self.scoring }
+    pub fn numeric_tolerance(&self) -> Tolerance { self.numeric_tolerance }
+    pub fn fuzzy(&self) -> usize { self.fuzzy }
+    pub fn normalize(&self) -> bool { self.normalize }
+    pub fn typed(&self) -> bool { self.typed }
+    pub fn show_explanation(&self) -> bool { self.show_explanation }
+    pub fn hint_penalty(&self) -> f32 { self.hint_penalty }
+    pub fn justified(&self) -> bool { self.justified }
+    pub fn ask_inverse(&self) -> bool { self.ask_inverse }
+    pub fn allow_review(&self) -> bool { self.allow_review }
+    pub fn repeat_missed(&self) -> bool { self.repeat_missed }
+    pub fn mastery(&self) -> usize { self.mastery }
+    pub fn pool(&self) -> Option<&str> { self.pool.as_deref() }
+    pub fn sample(&self) -> Option<usize> { self.sample }
+    pub fn seed(&self) -> Option<u64> { self.seed }
+    pub fn time_limit(&self) -> Option<Duration> { self.time_limit }
+    pub fn title(&self) -> Option<&str> { self.title.as_deref() }
+    pub fn author(&self) -> Option<&str> { self.author.as_deref() }
+    pub fn version(&self) -> Option<&str> { self.version.as_deref() }
+    pub fn description(&self) -> Option<&str> { self.description.as_deref() }
+
+    /// The `tags` option, split at commas
+    pub fn tags(&self) -> Vec<&str> {
+        self.tags.iter()
+            .flat_map(|tags| tags.split(','))
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+}
+
 impl std::str::FromStr for Config {
     type Err = ConfigError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -226,26 +419,14 @@ impl std::str::FromStr for Config {
 
 impl std::fmt::Display for Config {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{{\n    \
-            value: {0:.0}\n    \
-            case-sensitive: {1}\n    \
-            ordered: {2}\n    \
-            ordered-answers: {3}\n    \
-            tutorial: {4}\n    \
-            show-answer: {5}\n    \
-            show-value: {6}\n    \
-            show-quiz-info: {7}\n\
-            }}",
-
-            self.value,
-            self.case_sensitive,
-            self.ordered,
-            self.ordered_answers,
-            self.tutorial,
-            self.show_answer,
-            self.show_value,
-            self.show_quiz_info,
-        )
+        writeln!(f, "{{")?;
+        for option in Config::OPTIONS {
+            match option.value(self) {
+                value if value.is_empty() => writeln!(f, "    {0}: none", option.name)?,
+                value => writeln!(f, "    {0}: {1}", option.name, value)?,
+            }
+        }
+        write!(f, "}}")
     }
 }
 
@@ -253,6 +434,30 @@ impl std::fmt::Display for Config {
 pub enum Answer {
     Correct(String),
     Incorrect(String),
+    /// A correct answer that's a number, written `+= <number> [± <tolerance>[%]] [unit]`
+    Numeric(NumericAnswer),
+    /// A correct answer that's a regular expression, written `+re /<pattern>/`
+    Regex(Regex),
+}
+
+impl Answer {
+    pub fn is_correct(&self) -> bool {
+        matches!(self, Self::Correct(_) | Self::Numeric(_) | Self::Regex(_))
+    }
+
+    /// Whether the answer can only be typed (it can't be presented as an option).
+    pub fn is_typed_only(&self) -> bool {
+        matches!(self, Self::Numeric(_) | Self::Regex(_))
+    }
+
+    /// The answer as written.
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Correct(text) | Self::Incorrect(text) => text,
+            Self::Numeric(numeric) => numeric.text(),
+            Self::Regex(regex) => regex.source(),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -260,6 +465,12 @@ pub struct Question {
     title: String,
     answers: Vec<Answer>,
     config: Config,
+    /// Shown after the question is graded (`>` lines after the answers)
+    explanation: Option<String>,
+    /// Explanations for specific (incorrect) answers, keyed by index into `answers`
+    answer_explanations: BTreeMap<usize, String>,
+    /// Hints the quiz-taker can ask for (`~` lines), in the order they're given
+    hints: Vec<String>,
 }
 
 impl Question {
@@ -268,8 +479,58 @@ impl Question {
             title: String::new(),
             answers: Vec::new(),
             config: Config::default(),
+            explanation: None,
+            answer_explanations: BTreeMap::new(),
+            hints: Vec::new(),
         }
     }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The question's answers, in the order they were written
+    pub fn answers(&self) -> &[Answer] {
+        &self.answers
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Why the correct answer is correct, if the quiz explains it
+    pub fn explanation(&self) -> Option<&str> {
+        self.explanation.as_deref()
+    }
+
+    /// Why the answer at `index` (into `answers()`) is wrong, if the quiz explains it
+    pub fn answer_explanation(&self, index: usize) -> Option<&str> {
+        self.answer_explanations.get(&index).map(String::as_str)
+    }
+
+    pub fn hints(&self) -> &[String] {
+        &self.hints
+    }
+
+    /// An ID for the question that stays the same as long as its title and correct answers do,
+    /// even if the rest of the quiz (or the question's incorrect answers) change. Used to keep
+    /// track of it between sessions (see `study`).
+    pub fn id(&self) -> String {
+        let normalize = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        // the correct answers tell apart questions that share a title
+        let mut key = normalize(&self.title);
+        for answer in &self.answers {
+            match answer {
+                Answer::Correct(text) => key += &format!("\n+{0}", normalize(text)),
+                Answer::Numeric(numeric) => key += &format!("\n+={numeric}"),
+                Answer::Regex(regex) => key += &format!("\n+{regex}"),
+                Answer::Incorrect(_) => {},
+            }
+        }
+
+        format!("{0:016x}", fnv1a(key.as_bytes()))
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -304,6 +565,14 @@ pub enum QuestionErrorKind {
     NoCorrectAnswer,
     /// There is only config/comments, this is likely a comment block
     OnlyConfig,
+    /// A numeric answer (`+=`) couldn't be parsed
+    InvalidNumericAnswer(String),
+    /// A regular expression answer (`+re /.../`) couldn't be parsed
+    InvalidRegexAnswer(String, ParseRegexError),
+    /// A regular expression answer is in a question that would otherwise be multiple choice
+    RegexInChoiceQuestion,
+    /// A `>-` answer explanation has no `-` answer before it to explain
+    UnexplainedAnswer,
 }
 
 impl std::fmt::Display for QuestionErrorKind {
@@ -313,6 +582,10 @@ impl std::fmt::Display for QuestionErrorKind {
             Self::ConfigError(e) => write!(f, "{e}"),
             Self::NoCorrectAnswer => write!(f, "no correct answer"),
             Self::OnlyConfig => write!(f, "only config (likely a comment)"),
+            Self::InvalidNumericAnswer(ans) => write!(f, "invalid numeric answer '{ans}': {0}", ParseNumericAnswerError),
+            Self::InvalidRegexAnswer(ans, e) => write!(f, "invalid regular expression answer '/{ans}/': {e}"),
+            Self::RegexInChoiceQuestion => write!(f, "regular expression answers can only be typed, but the question has choices (set `typed: true` to type them)"),
+            Self::UnexplainedAnswer => write!(f, "`>-` explanation without a `-` answer before it"),
         }
     }
 }
@@ -321,8 +594,8 @@ fn to_context_string(s: &str) -> String {
     let max_len = 32;
     let indx = cmp::min(max_len,
         cmp::min(
-            s.find('\n').unwrap_or(std::usize::MAX),
-            s.find('\r').unwrap_or(std::usize::MAX),
+            s.find('\n').unwrap_or(usize::MAX),
+            s.find('\r').unwrap_or(usize::MAX),
         ),
     );
 
@@ -335,6 +608,14 @@ fn to_context_string(s: &str) -> String {
 
 impl Question {
     fn parse_str(base_config: &Config, q_text: &str) -> Result<Self, QuestionError> {
+        Self::parse_str_all(base_config, q_text).map_err(|mut errors| errors.swap_remove(0))
+    }
+
+    /// Parse a question, reporting every config error (and a missing correct answer) instead of
+    /// only the first error found.
+    ///
+    /// The returned `Vec` is never empty.
+    fn parse_str_all(base_config: &Config, q_text: &str) -> Result<Self, Vec<QuestionError>> {
         let mut question = Question::new();
 
         let mut lines_parsed = 0;
@@ -351,10 +632,10 @@ impl Question {
             None => {
                 // if text starts with `?` (no newline) it's just a question with no config
                 if q_text.starts_with('?') {
-                    ("", q_text[..].trim_start_matches('?').trim())
+                    ("", q_text.trim_start_matches('?').trim())
                 // else, everything is config/comment
                 } else {
-                    (&q_text[..], "") // questions MUST start with `?` marker
+                    (q_text, "") // questions MUST start with `?` marker
                 }
             },
             //None => ("",q_text[..].trim_start_matches('?')), // makes comment blocks harder
@@ -363,74 +644,168 @@ impl Question {
         // set up the context to return when the user
         let question_context = to_context_string(q_text);
 
-        let config = Config::parse_str(base_config, config_str);
+        let (config, mut config_errors) = Config::parse_str_all_in(Scope::Question, &base_config.question_base(), config_str);
+
+        // a question's `sample` is the size of its pool, so there has to be one
+        if config.sample.is_some() && config.pool.is_none() {
+            config_errors.push(ConfigError {
+                kind: ConfigErrorKind::SampleWithoutPool,
+                lines_parsed: Config::line_setting(config_str, "sample"),
+                context: "sample".to_owned(),
+            });
+        }
 
         lines_parsed += config_str.matches('\n').count();
 
-        question.config = match config {
-            // if the config errors that it's missing a delimiter *and* we know there's no
-            // question, there's a good chance that the quiz *meant* to put in a question (as
-            // opposed to a comment block) and forgot the delimiter `?`
-            Err(cfg_err) if matches!(cfg_err.kind, ConfigErrorKind::MissingDelimiter) && q_text.is_empty() => return Err(QuestionError {
+        // if the config errors that it's missing a delimiter *and* we know there's no
+        // question, there's a good chance that the quiz *meant* to put in a question (as
+        // opposed to a comment block) and forgot the delimiter `?`
+        if q_text.is_empty()
+            && let Some(cfg_err) = config_errors.iter().find(|e| matches!(e.kind, ConfigErrorKind::MissingDelimiter))
+        {
+            return Err(vec![QuestionError {
                 kind: QuestionErrorKind::MissingDelimiter,
                 lines_parsed: cfg_err.lines_parsed, // we errored before the config was
                 // over, so it knows the actual line number
                 context: question_context, // show the start
                 // of the would-be question
-            }),
-            // propogate other errors
-            Err(cfg_err) => return Err(QuestionError {
+            }]);
+        }
+
+        // propogate other errors
+        let mut errors: Vec<QuestionError> = config_errors.into_iter()
+            .map(|cfg_err| QuestionError {
                 lines_parsed: cfg_err.lines_parsed,
                 kind: QuestionErrorKind::ConfigError(cfg_err),
-                context: question_context,
-            }),
-            // else just handle it normally
-            Ok(cfg) => cfg,
-        };
+                context: question_context.clone(),
+            })
+            .collect();
+
+        if !errors.is_empty() && q_text.is_empty() {
+            return Err(errors);
+        }
+
+        question.config = config;
 
         if q_text.is_empty() {
-            return Err(QuestionError {
+            return Err(vec![QuestionError {
                 kind: QuestionErrorKind::OnlyConfig,
                 context: to_context_string(q_text),
                 lines_parsed,
-            });
+            }]);
         }
 
         // parse question
         // parse answers
         let mut remaining = q_text;
+        // `>-` explanation lines explain the closest `-` answer before them, `>` lines explain the
+        // question
+        let mut last_incorrect = None;
         while !remaining.is_empty() {
             let part_end = std::cmp::min(
-                // each answer starts with \n(+|-), pick the closest one
-                std::cmp::min(
-                    remaining[1..].find("\n+").unwrap_or(std::usize::MAX),
-                    remaining[1..].find("\n-").unwrap_or(std::usize::MAX),
-                ), remaining.len()-2 // or consume the rest (+2 later) if neither found
+                // each answer (or explanation, or hint) starts with \n(+|-|>|~), pick the closest one
+                ["\n+", "\n-", "\n>", "\n~"].iter()
+                    .filter_map(|delim| remaining[1..].find(delim))
+                    .min()
+                    .unwrap_or(usize::MAX),
+                remaining.len()-2 // or consume the rest (+2 later) if none found
             ) + 2; // add two to consume that newline and split directly before the delimiter
             
             let to_parse;
             (to_parse, remaining) = remaining.split_at(part_end);
 
             match &to_parse[0..1] {
-                "+" => question.answers.push(Answer::Correct(
+                ">" => {
+                    let (explanation, text) = match to_parse[1..].strip_prefix('-') {
+                        Some(text) => match last_incorrect {
+                            Some(i) => (question.answer_explanations.entry(i).or_default(), text),
+                            None => {
+                                errors.push(QuestionError {
+                                    kind: QuestionErrorKind::UnexplainedAnswer,
+                                    lines_parsed,
+                                    context: question_context.clone(),
+                                });
+                                continue;
+                            },
+                        },
+                        None => (question.explanation.get_or_insert_default(), &to_parse[1..]),
+                    };
+                    let text = text.trim().replace("\r\n", " ").replace("\n", " ");
+
+                    if !explanation.is_empty() {
+                        explanation.push('\n');
+                    }
+                    explanation.push_str(&text);
+                },
+                "~" => question.hints.push(
                     to_parse[1..].trim().replace("\r\n", " ").replace("\n", " ")
-                )),
-                "-" => question.answers.push(Answer::Incorrect(
+                ),
+                // numeric answers
+                "+" if to_parse[1..].trim_start().starts_with('=') => {
+                    let text = to_parse[1..].trim_start()[1..].trim().replace("\r\n", " ").replace("\n", " ");
+
+                    match text.parse() {
+                        Ok(numeric) => question.answers.push(Answer::Numeric(numeric)),
+                        Err(_) => errors.push(QuestionError {
+                            kind: QuestionErrorKind::InvalidNumericAnswer(text),
+                            lines_parsed,
+                            context: question_context.clone(),
+                        }),
+                    }
+                },
+                // regular expression answers
+                "+" if to_parse[1..].trim_start().strip_prefix("re").map(str::trim)
+                    .is_some_and(|re| re.len() >= 2 && re.starts_with('/') && re.ends_with('/')) =>
+                {
+                    let trimmed = to_parse[1..].trim_start()[2..].trim();
+                    let pattern = trimmed[1..trimmed.len()-1].replace("\r\n", " ").replace("\n", " ");
+
+                    match pattern.parse() {
+                        Ok(regex) => question.answers.push(Answer::Regex(regex)),
+                        Err(e) => errors.push(QuestionError {
+                            kind: QuestionErrorKind::InvalidRegexAnswer(pattern, e),
+                            lines_parsed,
+                            context: question_context.clone(),
+                        }),
+                    }
+                },
+                "+" => question.answers.push(Answer::Correct(
                     to_parse[1..].trim().replace("\r\n", " ").replace("\n", " ")
                 )),
+                "-" => {
+                    last_incorrect = Some(question.answers.len());
+                    question.answers.push(Answer::Incorrect(
+                        to_parse[1..].trim().replace("\r\n", " ").replace("\n", " ")
+                    ));
+                },
                 _ => question.title = to_parse.trim().replace("\r\n", " ").replace("\n", " "),
             }
         }
 
-        // err if there are no correct answers
-        if question.answers.iter().filter(|ans| matches!(ans, Answer::Correct(_))).count() == 0 {
-            return Err(QuestionError {
+        // patterns can't be shown as choices, so a question with them has to be typed
+        let choices = question.answers.iter().filter(|answer| !answer.is_typed_only()).count();
+        if question.answers.iter().any(|answer| matches!(answer, Answer::Regex(_))) && choices > 1 && !question.config.typed {
+            errors.push(QuestionError {
+                kind: QuestionErrorKind::RegexInChoiceQuestion,
+                lines_parsed,
+                context: question_context.clone(),
+            });
+        }
+
+        // err if there are no correct answers (unless the only ones were invalid numeric/regex
+        // answers)
+        if !question.answers.iter().any(Answer::is_correct) && errors.is_empty() {
+            errors.push(QuestionError {
                 kind: QuestionErrorKind::NoCorrectAnswer,
-                lines_parsed: lines_parsed,
+                lines_parsed,
                 context: question_context,
             });
         }
 
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
         Ok(question)
     }
 }
@@ -442,6 +817,73 @@ impl std::str::FromStr for Question {
     }
 }
 
+/// A named part of a quiz, started by a `## Title` line.
+///
+/// The config lines after the title are the section's config, which is layered between the
+/// file-level config and the config of its questions. Questions with `ordered: false` are only
+/// shuffled within their section.
+#[derive(Debug, PartialEq)]
+pub struct Section {
+    title: String,
+    config: Config,
+    /// Indices (into `Quiz::questions`) of the section's questions
+    questions: Range<usize>,
+}
+
+impl Section {
+    /// Parse a section's heading block: the `## Title` line, then the section's config. A question
+    /// can follow the config in the same block, it's returned (unparsed) with the number of lines
+    /// before it.
+    ///
+    /// The section has no questions yet.
+    fn parse_str_all<'t>(file_config: &Config, text: &'t str) -> (Self, Option<(usize, &'t str)>, Vec<ConfigError>) {
+        let (heading, body) = text.split_once('\n').unwrap_or((text, ""));
+
+        let (config_str, question) = match body.find("\n?") {
+            _ if body.starts_with('?') => ("", Some(body)),
+            Some(i) => (&body[..=i], Some(&body[i + 1..])),
+            None => (body, None),
+        };
+
+        let (config, mut errors) = Config::parse_str_all_in(Scope::Question, &file_config.question_base(), config_str);
+
+        // a time limit for a whole section isn't supported (and questions wouldn't inherit it)
+        if config.time_limit.is_some() {
+            errors.push(ConfigError {
+                kind: ConfigErrorKind::NotForSections,
+                lines_parsed: Config::line_setting(config_str, "time-limit"),
+                context: "time-limit".to_owned(),
+            });
+        }
+
+        // the heading is the section's first line
+        for error in &mut errors {
+            error.lines_parsed += 1;
+        }
+
+        let section = Section {
+            title: heading.trim().trim_start_matches('#').trim().to_owned(),
+            config,
+            questions: 0..0,
+        };
+
+        (section, question.map(|question| (1 + config_str.matches('\n').count(), question)), errors)
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Indices (into `Quiz::questions`) of the section's questions
+    pub fn questions(&self) -> Range<usize> {
+        self.questions.clone()
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Quiz {
     /// The File/Quiz -level config
@@ -450,10 +892,54 @@ pub struct Quiz {
     /// The questions in the quiz
     pub questions: Vec<Question>,
 
+    /// The quiz's named parts (`## Title` lines), in the order they were written
+    pub sections: Vec<Section>,
+
     /// Total point value of all questions combined / max-score
+    ///
+    /// Penalties don't count against the total, they only lower the quiz-taker's score (which
+    /// may end up negative).
     pub total_score: f32,
 }
 
+/// Information about a quiz, from its `title`, `author`, `version`, `description`, and `tags`
+/// options.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl Metadata {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::fmt::Display for Metadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (label, value) in [
+            ("Title", &self.title),
+            ("Author", &self.author),
+            ("Version", &self.version),
+            ("Description", &self.description),
+        ] {
+            if let Some(value) = value {
+                writeln!(f, "{label}: {value}")?;
+            }
+        }
+
+        if !self.tags.is_empty() {
+            writeln!(f, "Tags: {0}", self.tags.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct QuizError {
     kind: QuizErrorKind,
@@ -506,6 +992,93 @@ impl From<QuestionError> for QuizErrorKind {
 impl std::str::FromStr for Quiz {
     type Err = QuizError;
     fn from_str(quiz_str: &str) -> Result<Self, Self::Err> {
+        Quiz::parse_all(quiz_str).map_err(|mut errors| errors.swap_remove(0))
+    }
+}
+
+impl Quiz {
+    /// The quiz's title, author, and the like.
+    pub fn metadata(&self) -> Metadata {
+        let config = &self.config;
+
+        Metadata {
+            title: config.title.clone(),
+            author: config.author.clone(),
+            version: config.version.clone(),
+            description: config.description.clone(),
+            tags: config.tags().into_iter().map(str::to_owned).collect(),
+        }
+    }
+
+    /// Index (into `Quiz::sections`) of the section the question at `question` (an index into
+    /// `Quiz::questions`) is in, if it's in one.
+    pub fn section_of(&self, question: usize) -> Option<usize> {
+        self.sections.iter().position(|section| section.questions.contains(&question))
+    }
+
+    /// A hash of everything in the quiz, for telling whether it's changed (comments and formatting
+    /// don't count, since they don't change the quiz).
+    pub fn fingerprint(&self) -> u64 {
+        fnv1a(self.canonical().as_bytes())
+    }
+
+    /// The quiz written out in a fixed form, close to how it would be written by hand.
+    ///
+    /// Only options that aren't at their default are written, so adding an option doesn't change
+    /// every quiz's fingerprint.
+    fn canonical(&self) -> String {
+        fn write_config(out: &mut String, config: &Config) {
+            for option in Config::OPTIONS {
+                let value = option.value(config);
+                if value != option.default_value() {
+                    out.push_str(&format!(";{0}: {value}\n", option.name));
+                }
+            }
+        }
+
+        let mut out = String::new();
+        write_config(&mut out, &self.config);
+        out.push_str("---\n");
+
+        for section in &self.sections {
+            out.push_str(&format!("\n## {0} ({1}..{2})\n", section.title, section.questions.start, section.questions.end));
+            write_config(&mut out, &section.config);
+        }
+
+        for question in &self.questions {
+            out.push('\n');
+            write_config(&mut out, &question.config);
+            out.push_str(&format!("?{0}\n", question.title));
+
+            for (i, answer) in question.answers.iter().enumerate() {
+                match answer {
+                    Answer::Correct(text) => out.push_str(&format!("+{text}\n")),
+                    Answer::Incorrect(text) => out.push_str(&format!("-{text}\n")),
+                    Answer::Numeric(numeric) => out.push_str(&format!("+={numeric}\n")),
+                    Answer::Regex(regex) => out.push_str(&format!("+re {regex}\n")),
+                }
+                if let Some(explanation) = question.answer_explanation(i) {
+                    out.push_str(&format!(">-{explanation}\n"));
+                }
+            }
+
+            if let Some(explanation) = &question.explanation {
+                out.push_str(&format!(">{explanation}\n"));
+            }
+            for hint in &question.hints {
+                out.push_str(&format!("~{hint}\n"));
+            }
+        }
+
+        out
+    }
+
+    /// Parse a quiz, collecting *every* error in it rather than stopping at the first one.
+    ///
+    /// Valid questions are still parsed around broken ones so that all of a quiz's errors can be
+    /// reported (and fixed) at once. The errors are in the order they appear in the quiz and the
+    /// returned `Vec` is never empty.
+    pub fn parse_all(quiz_str: &str) -> Result<Self, Vec<QuizError>> {
         // split the quiz by the '---' separator between config and quiz
         let (config_str, quiz_text) = match quiz_str
             .trim_start_matches("---") // ignore any starting '---' prefixes (for empty config or
@@ -514,55 +1087,90 @@ impl std::str::FromStr for Quiz {
         {
             Some((cfg, qz)) => (cfg, qz),
             // again, ignore '---' at the start of the quiz for people who have empty config
-            None => ("", quiz_str[..].trim_start_matches("---")),
+            None => ("", quiz_str.trim_start_matches("---")),
         };
 
+        let mut errors = Vec::new();
+
         // parse the changes to default config
-        let config: Config = match config_str.parse() {
-            Ok(cfg) => cfg,
-            Err(cfg_err) => return Err(QuizError {
-                lines_parsed: cfg_err.lines_parsed,
-                kind: cfg_err.into(),
-            }),
-        };
+        let (config, config_errors) = Config::parse_str_all(&Config::default(), config_str);
+        errors.extend(config_errors.into_iter().map(|cfg_err| QuizError {
+            lines_parsed: cfg_err.lines_parsed,
+            kind: cfg_err.into(),
+        }));
 
         let mut quiz = Quiz {
             config,
             questions: Vec::new(),
+            sections: Vec::new(),
             total_score: 0.0,
         };
 
         // count the newlines in the config
         let mut lines_parsed = config_str.matches('\n').count();
-        if config_str.len() != 0 {
+        if !config_str.is_empty() {
             lines_parsed += 1; // add the line from `\n---`
         }
 
         // Questions are separated by newlines
-        for q_text in quiz_text
+        for block in quiz_text
             .split("\r\n\r\n") // handle windows blank lines
             .flat_map(|text| text.split("\n\n")) // handle normal linux blank lines
             {
+            // everything after a `## Title` line is in its section, until the next one
+            let heading = block.trim_start_matches(['\r', '\n']);
+            let mut q_text = block;
+            let mut q_lines_parsed = lines_parsed;
+
+            if heading.starts_with("##") {
+                let lines_before = lines_parsed + block[..block.len() - heading.len()].matches('\n').count();
+                let (mut section, question, section_errors) = Section::parse_str_all(&quiz.config, heading);
+
+                errors.extend(section_errors.into_iter().map(|cfg_err| QuizError {
+                    lines_parsed: lines_before + cfg_err.lines_parsed,
+                    kind: cfg_err.into(),
+                }));
+
+                section.questions = quiz.questions.len()..quiz.questions.len();
+                quiz.sections.push(section);
+
+                (q_lines_parsed, q_text) = match question {
+                    Some((lines, question)) => (lines_before + lines, question),
+                    None => (lines_parsed, ""),
+                };
+            }
+
+            let base_config = match quiz.sections.last() {
+                Some(section) => &section.config,
+                None => &quiz.config,
+            };
+
             if !q_text.is_empty() { 
-                match Question::parse_str(&quiz.config, q_text) {
-                    Err(question_err) if matches!(question_err.kind, QuestionErrorKind::OnlyConfig) => (), // don't push comment/config blocks
+                match Question::parse_str_all(base_config, q_text) {
+                    Err(question_errs) if matches!(question_errs[0].kind, QuestionErrorKind::OnlyConfig) => (), // don't push comment/config blocks
                     // as questions
 
-                    Err(e) => return Err(QuizError {
-                        lines_parsed: lines_parsed + e.lines_parsed, // where the question is +
+                    Err(question_errs) => errors.extend(question_errs.into_iter().map(|e| QuizError {
+                        lines_parsed: q_lines_parsed + e.lines_parsed, // where the question is +
                         // where the error is in the question
                         kind: QuizErrorKind::QuestionError(e),
-                    }),
+                    })),
                     
-                    Ok(question) => quiz.questions.push(question), // else just return errors / add the question
+                    Ok(question) => {
+                        quiz.questions.push(question); // else just return errors / add the question
+                        if let Some(section) = quiz.sections.last_mut() {
+                            section.questions.end = quiz.questions.len();
+                        }
+                    },
                 }
             }
 
             // 2 for the two newlines before each question-block + the newlines in the question
-            lines_parsed += 2 + q_text.matches('\n').count();
-            if q_text.is_empty() { // account for blank lines
-                //lines_parsed += 1;
-            }
+            lines_parsed += 2 + block.matches('\n').count();
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
         }
 
         // add up the total score of all questions
@@ -572,228 +1180,447 @@ impl std::str::FromStr for Quiz {
         
         Ok(quiz)
     }
+
+    /// Take the quiz in the terminal, reading answers from stdin and writing to stdout.
+    pub fn take(&self) -> io::Result<QuizResult> {
+        self.take_with(stdin().lock(), stdout())
+    }
+
+    /// Take the quiz, reading the quiz-taker's answers from `input` and writing the quiz to
+    /// `output`.
+    ///
+    /// Returns the quiz-taker's results.
+    pub fn take_with<R: BufRead, W: Write>(&self, input: R, output: W) -> io::Result<QuizResult> {
+        let mut session = QuizSession::new(self);
+        session.take(input, output)?;
+        Ok(session.result())
+    }
 }
 
-impl Quiz {
-    pub fn take(&self) -> io::Result<f32> {
-        let input = stdin();
+impl QuizSession<'_> {
+    /// Take the session's quiz, reading the quiz-taker's answers from `input` and writing the quiz
+    /// to `output`.
+    ///
+    /// If `input` runs out before the quiz is finished, the quiz stops at the current question
+    /// (so it can be resumed later).
+    ///
+    /// Returns the quiz-taker's score.
+    pub fn take<R: BufRead, W: Write>(&mut self, input: R, output: W) -> io::Result<f32> {
+        self.take_autosaving(input, output, |_| Ok(()))
+    }
+
+    /// Take the session's quiz like `QuizSession::take`, passing the attempt to `save` after every
+    /// answer so it can be resumed if the quiz-taker stops partway through (see
+    /// `QuizSession::resume`).
+    pub fn take_autosaving<R, W, S>(&mut self, mut input: R, mut output: W, mut save: S) -> io::Result<f32>
+    where
+        R: BufRead,
+        W: Write,
+        S: FnMut(&SavedAttempt) -> io::Result<()>,
+    {
+        let quiz = self.quiz();
 
-        if self.config.tutorial {
-            println!("\n\
+        if quiz.config.tutorial {
+            writeln!(output, "\n\
                 Hello, welcome to your quiz!\n\
                 I'll ask questions and you give the answers; sound good?\n\
-            ");
+            ")?;
 
-            println!("\
+            writeln!(output, "\
                 Questions that don't present options expect you to type your answer; \
                 questions that present options with parenthesis expect a single answer \
                 (type the number of the answer); and questions that present options with \
                 square brackets expect multiple answers (separate them with spaces, \
                 semicolons, periods, or commas).\n\
-            ");
+            ")?;
 
-            println!("\
+            writeln!(output, "\
                 Once you've typed your answer, press enter twice to submit. If you made a \
                 mistake, don't worry! Pressing enter only once allows you to restart the \
                 answering process with a new answer (the last non-empty line is used), no \
-                sweat!\n\
-            ");
-
-            println!("Your quiz starts now!\n---");
+                sweat! Submitting without any answer skips the question. Some questions have \
+                hints: submit `?hint` to get one (hints may cost you some of the question's \
+                points). Some quizzes and questions have time limits: answers given after the \
+                time runs out don't count. When practicing, questions you miss are asked again \
+                later (only your first try counts towards your score). Once the quiz is over, you \
+                can look back over your answers.\n\
+            ")?;
+
+            writeln!(output, "Your quiz starts now!\n---")?;
         }
 
-        if self.config.show_quiz_info {
-            println!("\
+        if quiz.config.show_quiz_info {
+            write!(output, "{0}", quiz.metadata())?;
+            writeln!(output, "\
                 Total Points: {0}\n\
                 Total Questions: {1}\n\
                 Base Config: {2} (may change per question)\n\
             ",
-                self.total_score,
-                self.questions.len(),
-                self.config,
-            )
+                self.total_score(),
+                self.progress().1,
+                quiz.config,
+            )?;
         }
 
-        let mut score = 0.0;
-
-        let mut questions = Vec::new();
-        questions.reserve_exact(self.questions.len());
-
-        let mut ordered_questions = vec![];
+        let (answered, total) = self.progress();
+        if answered > 0 && !self.is_finished() {
+            writeln!(output, "Resuming your attempt at question {0} of {total} (score so far: {1}).",
+                answered + 1, format_points(self.score()))?;
+        }
 
-        // set the order that questions will be asked in
-        for question in &self.questions {
-            if question.config.ordered {
-                ordered_questions.push(question);
-            } else {
-                questions.push(question);
-            }
+        if let Some(limit) = quiz.config.time_limit {
+            writeln!(output, "You have {0} to finish the quiz.", format_duration(limit))?;
         }
 
-        // randomly shuffle questions that desire to be randomly shuffled
-        shuffle(&mut questions);
+        let mut hints_used = 0;
+        let mut section = None;
+        self.start();
 
-        // append questions that desire to be presented in order (multi-part questions, etc)
-        questions.append(&mut ordered_questions);
+        // cloned so hints can be given while the question is being asked
+        'questions: while let Some(question) = self.current_question().cloned() {
+            writeln!(output)?;
 
-        for question in questions {
-            println!();
+            // show the section's heading when it starts
+            let question_section = quiz.section_of(question.index());
+            if question_section != section {
+                if let Some(i) = question_section {
+                    writeln!(output, "--- {0} ---\n", quiz.sections[i].title())?;
+                }
+                section = question_section;
+            }
+
+            let config = question.config();
 
             // show question value
-            if question.config.show_value {
-                println!("For {0:.0} pt(s):", question.config.value);
+            if config.show_value {
+                writeln!(output, "For {0} pt(s):", format_points(config.value))?;
             }
 
             // ask question
-            println!("{0}", question.title);
+            if question.is_repeat() {
+                writeln!(output, "(Again, since you missed it earlier)")?;
+            }
+            if question.is_inverse() {
+                writeln!(output, "(Inverse of an earlier question) What was asked, given the answer?")?;
+            }
+            writeln!(output, "{0}", question.title())?;
 
-            // prep user input
-            let mut user_in = String::new();
+            let kind = question.kind();
 
-            // handle typed-answer questions
-            if question.answers.len() == 1 {
-                print!("\nYour Answer: ");
-                stdout().flush()?;
-                input.read_line(&mut user_in)?;
+            // display answers
+            for (i, text) in question.options().iter().enumerate() {
+                if kind == QuestionKind::SingleChoice {
+                    writeln!(output, "({i}) {text}")?;
+                } else {
+                    writeln!(output, "[{i}] {text}")?;
+                }
+            }
 
-                let mut user_answer = String::new();
-                while !user_in.trim().is_empty() {
-                    user_answer = user_in.clone();
-                    
-                    user_in.clear();
-                    input.read_line(&mut user_in)?;
+            let hints = question.question().hints().len();
+            if hints > 0 {
+                write!(output, "\n({hints} hint(s) available, submit `?hint` to get one")?;
+                if config.hint_penalty > 0.0 {
+                    write!(output, ", each costs {0}% of the points", format_points(config.hint_penalty * 100.0))?;
                 }
+                writeln!(output, ")")?;
+            }
 
-                let mut ans = match &question.answers[0] {
-                    Answer::Correct(ans) => ans,
-                    Answer::Incorrect(_) => unreachable!(), // this question would fail to parse
-                    // with `NoCorrectAnswer`
-                }.to_owned();
+            if let Some(remaining) = self.time_remaining() {
+                writeln!(output, "\n(Time left: {0})", format_duration(remaining))?;
+            }
 
-                if !question.config.case_sensitive {
-                    user_answer = user_answer.to_lowercase();
-                    ans = ans.to_lowercase();
+            let prompt = if kind == QuestionKind::MultipleChoice { "Your Answers: " } else { "Your Answer: " };
+            write!(output, "\n{prompt}")?;
+            output.flush()?;
+
+            let Some(mut user_answer) = read_answer(&mut input)? else { break };
+            while user_answer.trim() == "?hint" {
+                match self.hint() {
+                    Some(hint) => {
+                        writeln!(output, "Hint: {hint}")?;
+                        save(&self.save())?;
+                    },
+                    None => writeln!(output, "There are no more hints for this question.")?,
                 }
 
-                let is_correct = ans == user_answer.trim();
+                write!(output, "\n{prompt}")?;
+                output.flush()?;
+                let Some(answer) = read_answer(&mut input)? else { break 'questions };
+                user_answer = answer;
+            }
+
+            let response = question.parse_response(&user_answer);
+
+            let mut justification = String::new();
+            if config.justified && !response.is_empty() {
+                write!(output, "Justify your answer: ")?;
+                output.flush()?;
+                justification = read_answer(&mut input)?.unwrap_or_default();
+            }
+
+            let show_answer = config.show_answer;
+            let asked = question.question();
+            let answers = asked.answers();
+            let mut feedback = self.submit(response).expect("the session has a current question");
+            hints_used += feedback.hints_used;
+            self.justify(&justification);
+
+            if feedback.timed_out {
+                writeln!(output, "Time's up! Answers given after the time runs out don't count.")?;
+            }
+
+            // like the penalty below, this gives away how the answer was graded
+            if show_answer
+                && !feedback.is_correct
+                && let Some(Answer::Incorrect(text)) = feedback.matched_answer.map(|i| &answers[i])
+            {
+                writeln!(output, "'{text}' is a known incorrect answer.")?;
+            }
+
+            if feedback.accepted_with_typo
+                && let Some(ans) = feedback.matched_answer.map(|i| &answers[i])
+            {
+                writeln!(output, "Accepted with typo, correct spelling is '{0}'", ans.text())?;
+            }
+
+            if show_answer && feedback.points < 0.0 {
+                writeln!(output, "Penalty: {0} pt(s)", format_points(feedback.points))?;
+            }
+
+            if show_answer {
+                if !feedback.is_correct && feedback.points > 0.0 {
+                    writeln!(output, "Partial credit: {0}/{1} pt(s)", format_points(feedback.points), format_points(config.value))?;
+                }
 
-                if is_correct {
-                    score += question.config.value;
+                match feedback.correct_response {
+                    _ if feedback.is_correct => writeln!(output, "Correct!")?,
+                    Response::Text(ans) => writeln!(output, "Correct Answer: '{ans}'")?,
+                    Response::Choices(correct_answer_indicies) => writeln!(output, "Correct Answer{0}: {1}",
+                        if kind == QuestionKind::SingleChoice {""} else {"s"},
+                        correct_answer_indicies.iter()
+                            .map(|e| e.to_string())
+                            .collect::<Vec<String>>() // could you iter.intersprese, but didn't want to
+                            // use a String (instead of a &str)
+                            .join(", ")
+                    )?,
                 }
+            }
 
-                if question.config.show_answer {
-                    if is_correct {
-                        println!("Correct!\n");
-                    } else {
-                        println!("Correct Answer: '{ans}'\n");
+            if show_answer || config.show_explanation {
+                for &i in &feedback.selected_answers {
+                    if let (Answer::Incorrect(text), Some(explanation)) = (&answers[i], asked.answer_explanation(i)) {
+                        writeln!(output, "'{text}': {explanation}")?;
                     }
                 }
 
-                continue;
-            }
+                if let Some(explanation) = asked.explanation() {
+                    writeln!(output, "Explanation: {explanation}")?;
+                }
 
-            // multiple-choice/answer questions
-            let num_correct_answers = question.answers
-                .iter()
-                .filter(|t| matches!(t, Answer::Correct(_)))
-                .count();
+                writeln!(output)?;
+            }
 
-            let single_correct = num_correct_answers == 1;
+            if config.allow_review && !feedback.is_correct && !feedback.skipped {
+                write!(output, "Mark your answer as correct anyway? It will be logged for review (y/N): ")?;
+                output.flush()?;
 
-            let mut answers: Vec<&Answer> = question.answers.iter().collect();
+                if read_answer(&mut input)?.unwrap_or_default().trim().to_lowercase().starts_with('y')
+                    && let Some(reviewed) = self.mark_correct()
+                {
+                    feedback = reviewed;
+                    writeln!(output, "Marked as correct for {0} pt(s).\n", format_points(feedback.points))?;
+                }
+            }
 
-            if !question.config.ordered_answers {
-                shuffle(&mut answers);
+            if feedback.will_repeat {
+                writeln!(output, "You'll see this question again later.")?;
             }
 
-            let mut correct_answer_indicies = vec![];
+            save(&self.save())?;
+        }
 
-            // display answers
-            for (i, answer) in answers.iter().enumerate() {
-                let text = match answer {
-                    Answer::Incorrect(text) => text,
-                    Answer::Correct(text) => { correct_answer_indicies.push(i); text },
-                };
+        let score = self.score();
+
+        if !self.is_finished() {
+            let (answered, total) = self.progress();
+            writeln!(output, "\n\nThe input ended before the quiz was finished, so it stopped at question {0} of {total} (score so far: {1}).",
+                answered + 1, format_points(score))?;
+            return Ok(score);
+        }
 
-                if single_correct {
-                    println!("({i}) {text}");
+        if self.timed_out() {
+            writeln!(output, "\nThe quiz's time ran out, so the remaining questions were left unanswered.")?;
+        }
+
+        writeln!(output, "\n\nQuiz finished!")?;
+        writeln!(output, "Your score: {0}/{1} ({2:.0}%)", format_points(score), format_points(self.total_score()), score*100.0/self.total_score())?;
+
+        for section in self.section_scores() {
+            writeln!(output, "    {0}: {1}/{2} ({3:.0}%)",
+                quiz.sections[section.section].title(),
+                format_points(section.score),
+                format_points(section.total_score),
+                section.percent(),
+            )?;
+        }
+
+        if hints_used > 0 {
+            writeln!(output, "Hints used: {hints_used}")?;
+        }
+
+        if quiz.config.time_limit.is_some() {
+            let time_taken = self.feedback().iter().map(|feedback| feedback.time_spent).sum();
+            writeln!(output, "Time taken: {0}", format_duration(time_taken))?;
+        }
+
+        if !self.mastery().is_empty() {
+            writeln!(output, "\nPractice:")?;
+            for mastery in self.mastery() {
+                write!(output, "    {0}: ", mastery.title)?;
+                if mastery.is_mastered() {
+                    writeln!(output, "mastered after {0} tries ({1} missed)", mastery.attempts, mastery.misses)?;
                 } else {
-                    println!("[{i}] {text}");
+                    writeln!(output, "not mastered yet ({0}/{1} correct in a row)", mastery.streak, mastery.required)?;
                 }
             }
+        }
 
-            print!("\nYour Answer{0}: ", if single_correct {""} else {"s"});
-            stdout().flush()?;
+        if let Some(seed) = self.seed() {
+            writeln!(output, "Quiz seed: {seed} (use it to replay this quiz in the same order)")?;
+        }
+
+        if !self.feedback().is_empty() {
+            write!(output, "\nReview your answers? Type `all` or `missed` (or nothing to skip): ")?;
+            output.flush()?;
 
-            correct_answer_indicies.sort();
+            let filter = match read_answer(&mut input)?.unwrap_or_default().trim().to_lowercase().as_str() {
+                "all" | "a" => Some(ReviewFilter::All),
+                "missed" | "m" => Some(ReviewFilter::Missed),
+                _ => None,
+            };
+
+            if let Some(filter) = filter {
+                self.write_review(&mut output, filter)?;
+            }
+        }
+
+        Ok(score)
+    }
 
-            input.read_line(&mut user_in)?;
+    /// Write a review of the questions answered so far: the answer given, the correct answers,
+    /// the points earned, and the explanations.
+    ///
+    /// Questions with `show_answer: false` only show the answer given (and their explanations,
+    /// with `show_explanation`). They're left out of `ReviewFilter::Missed`, since that would
+    /// give away whether they were missed.
+    pub fn write_review<W: Write>(&self, mut output: W, filter: ReviewFilter) -> io::Result<()> {
+        let result = self.result();
+        let mut reviewed = 0;
+
+        for (n, (question, (asked, feedback))) in result.questions.iter().zip(self.first_tries()).enumerate() {
+            let config = asked.config();
+            if filter == ReviewFilter::Missed && (question.is_correct || !config.show_answer) {
+                continue;
+            }
+            reviewed += 1;
 
-            let mut user_answers = vec![];
+            writeln!(output, "\n{0}. {1}", n + 1, question.title)?;
 
-            while !user_in.trim().is_empty() {
-                user_answers = user_in
-                    .split(['.', ' ', ';', ','])
-                    .map(|s| s.trim())
-                    .filter(|s| !s.is_empty())
-                    .filter_map(|s| s.parse::<usize>().ok())
-                    .collect();
-                user_answers.sort();
-                
-                user_in.clear();
-                input.read_line(&mut user_in)?;
+            let answer = match question {
+                QuestionResult { skipped: true, timed_out: true, .. } => "(none, time ran out)",
+                QuestionResult { skipped: true, .. } => "(skipped)",
+                _ => &question.answer,
+            };
+            writeln!(output, "   Your Answer: {answer}")?;
+
+            if config.show_answer {
+                writeln!(output, "   Correct Answer{0}: {1}",
+                    if question.correct_answers.len() == 1 {""} else {"s"},
+                    question.correct_answers.join(", "),
+                )?;
+                writeln!(output, "   Points: {0}/{1}", format_points(question.points), format_points(question.value))?;
             }
 
-            let is_correct = user_answers == correct_answer_indicies;
+            if config.show_answer || config.show_explanation {
+                let answers = asked.question().answers();
+                for &i in &feedback.selected_answers {
+                    if let (Answer::Incorrect(text), Some(explanation)) = (&answers[i], asked.question().answer_explanation(i)) {
+                        writeln!(output, "   '{text}': {explanation}")?;
+                    }
+                }
 
-            if is_correct {
-                score += question.config.value;
+                if let Some(explanation) = asked.question().explanation() {
+                    writeln!(output, "   Explanation: {explanation}")?;
+                }
             }
+        }
 
-            if question.config.show_answer {
-                if is_correct {
-                    println!("Correct!");
-                } else {
-                    println!("Correct Answer{0}: {1}\n", if single_correct {""} else {"s"}, correct_answer_indicies.iter()
-                        .map(|e| e.to_string())
-                        .collect::<Vec<String>>() // could you iter.intersprese, but didn't want to
-                        // use a String (instead of a &str)
-                        .join(", ")
-                    );
-                }
+        if reviewed == 0 {
+            match filter {
+                ReviewFilter::All => writeln!(output, "\nNo questions were answered.")?,
+                ReviewFilter::Missed => writeln!(output, "\nNo missed questions to review.")?,
             }
+        }
 
-            // BUNCHA PARTIAL CREDIT STUFF I DON'T CARE ABOUT RIGHT NOW
-            //// get the value for each part of the question
-            //let part_value = question.config.value / answers.len() as f32;
-            //
-            //// add the score of the incorrect answers (score will be subtracted if user submits an
-            //// incorrect answer)
-            //score += (answers.len() - num_correct_answers) as f32 * part_value;
-            //
-            //for ans in user_answers {
-            //
-            //}
-        };
+        Ok(())
+    }
+}
 
-        println!("\n\nQuiz finished!");
-        println!("Your score: {score:.0}/{0:.0} ({1:.0}%)", self.total_score, score*100.0/self.total_score);
+/// Which questions `QuizSession::write_review` goes over.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReviewFilter {
+    /// Every question answered
+    All,
+    /// Only the questions that weren't answered correctly
+    Missed,
+}
 
-        Ok(score)
-    }
+/// 64-bit FNV-1a, for hashes that have to stay the same between runs (`DefaultHasher` isn't
+/// guaranteed to be the same between releases).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3))
+}
+
+/// Format a point value with at most two decimal places (and no trailing zeros).
+fn format_points(points: f32) -> String {
+    // a question without a penalty takes away -0 points, which shouldn't show up as "-0"
+    let points = if points == 0.0 { 0.0 } else { points };
+    let formatted = format!("{points:.2}");
+    formatted.trim_end_matches('0').trim_end_matches('.').to_owned()
 }
 
-fn shuffle<T>(vec: &mut [T]) {
-    let n = vec.len();
-    if n == 0 { return }
-    for i in 0..(n - 1) {
-        let j = rand() % (n - i) + i;
-        vec.swap(i, j);
+/// Format a length of time in hours, minutes, and seconds (ex. `1m 30s`).
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / (60 * 60), seconds / 60 % 60, seconds % 60);
+
+    match (hours, minutes) {
+        (0, 0) => format!("{seconds}s"),
+        (0, _) => format!("{minutes}m {seconds}s"),
+        _ => format!("{hours}h {minutes}m {seconds}s"),
     }
 }
 
-fn rand() -> usize {
-    RandomState::new().build_hasher().finish() as usize
+/// Read the quiz-taker's answer from `input`.
+///
+/// The answer is submitted with a blank line; the last non-empty line before it is the answer.
+/// If `input` ends first, the last line is the answer, or there's no answer (`None`) if nothing
+/// was typed.
+fn read_answer<R: BufRead>(input: &mut R) -> io::Result<Option<String>> {
+    let mut user_in = String::new();
+    let mut answer = None;
+
+    loop {
+        user_in.clear();
+        if input.read_line(&mut user_in)? == 0 {
+            return Ok(answer);
+        }
+
+        if user_in.trim().is_empty() {
+            return Ok(Some(answer.unwrap_or_default()));
+        }
+        answer = Some(user_in.clone());
+    }
 }
 
 #[cfg(test)]
@@ -853,12 +1680,11 @@ mod tests {
         assert_eq!(res, expected)
     }
 
-    //#[test]
-    // there are no int options right now
+    #[test]
     fn config_invalid_value_int() {
-        let res = Config::parse_str(&Config::default(), ";something-that's-int: false").expect_err("should err with invalid value");
+        let res = Config::parse_str(&Config::default(), ";seed: false").expect_err("should err with invalid value");
         let expected = ConfigError {
-            kind: ConfigErrorKind::InvalidValue("false".parse::<i32>().expect_err("can't parse int from 'false'").into()),
+            kind: ConfigErrorKind::InvalidValue("false".parse::<u64>().expect_err("can't parse int from 'false'").into()),
             context: "false".to_owned(),
             lines_parsed: 0,
         };
@@ -876,8 +1702,10 @@ mod tests {
     fn config_opt_ignore_separation() {
         let res = Config::parse_str(&Config::default(), "; v a-l_uE :1.5").expect("value config option should parse");
 
-        let mut expected = Config::default();
-        expected.value = 1.5;
+        let expected = Config {
+            value: 1.5,
+            ..Default::default()
+        };
 
         assert_eq!(res, expected)
     }
@@ -886,8 +1714,10 @@ mod tests {
     fn config_opt_f32() {
         let res = Config::parse_str(&Config::default(), "; value: 1.5").expect("value config option should parse");
 
-        let mut expected = Config::default();
-        expected.value = 1.5;
+        let expected = Config {
+            value: 1.5,
+            ..Default::default()
+        };
 
         assert_eq!(res, expected)
     }
@@ -896,12 +1726,94 @@ mod tests {
     fn config_opt_bool() {
         let res = Config::parse_str(&Config::default(), "; tutorial: false").expect("tutorial config option should parse");
 
-        let mut expected = Config::default();
-        expected.tutorial = false;
+        let expected = Config {
+            tutorial: false,
+            ..Default::default()
+        };
 
         assert_eq!(res, expected)
     }
 
+    #[test]
+    fn config_opt_reset_to_default() {
+        let base = Config {
+            case_sensitive: true,
+            value: 3.0,
+            seed: Some(4),
+            ..Default::default()
+        };
+
+        // bare names reset to the built-in default, not the base config
+        let res = Config::parse_str(&base, "; case-sensitive\n;seed").expect("bare option names should parse");
+
+        let expected = Config {
+            value: 3.0,
+            ..Default::default()
+        };
+
+        assert_eq!(res, expected);
+
+        // an empty value is still an error
+        let res = Config::parse_str(&base, "; case-sensitive:").expect_err("empty value should err");
+        assert_eq!(res.kind, ConfigErrorKind::InvalidValue("".parse::<bool>().expect_err("can't parse bool from ''").into()));
+    }
+
+    #[test]
+    fn config_reset() {
+        let base = Config {
+            case_sensitive: true,
+            value: 3.0,
+            scoring: Scoring::Proportional,
+            ..Default::default()
+        };
+
+        let res = Config::parse_str(&base, ";typed: true\n; reset\n;value: 2").expect("reset should parse");
+
+        let expected = Config {
+            value: 2.0,
+            ..Default::default()
+        };
+
+        assert_eq!(res, expected);
+
+        let quiz: Quiz = ";case-sensitive: true\n;value: 2\n---\n;reset\n?question\n+answer\n\n?question2\n+answer".parse().expect("quiz should parse");
+        assert_eq!(quiz.questions[0].config, Config::default());
+        assert_eq!(quiz.questions[1].config, quiz.config);
+        assert_eq!(quiz.total_score, 3.0);
+    }
+
+    #[test]
+    fn config_file_only_option() {
+        let res = Question::parse_str(&Config::default(), ";tutorial: false\n?question\n+answer").expect_err("file-only option on a question should err");
+
+        let expected = QuestionError {
+            kind: QuestionErrorKind::ConfigError(ConfigError {
+                kind: ConfigErrorKind::FileOnlyOption,
+                context: "tutorial".to_owned(),
+                lines_parsed: 0,
+            }),
+            lines_parsed: 0,
+            context: "question".to_owned(),
+        };
+
+        assert_eq!(res, expected);
+
+        let quiz: Quiz = ";seed: 3\n---\n?question\n+answer".parse().expect("file-only option in the file config should parse");
+        assert_eq!(quiz.config.seed(), Some(3));
+    }
+
+    #[test]
+    fn config_display() {
+        let config = Config::parse_str(&Config::default(), ";value: 2\n;scoring: proportional\n;seed: 7").expect("config should parse");
+        let display = config.to_string();
+
+        assert!(display.starts_with("{\n    value: 2.0\n    case-sensitive: false\n"));
+        assert!(display.contains("\n    scoring: proportional\n"));
+        assert!(display.contains("\n    seed: 7\n"));
+        assert!(display.contains("\n    title: none\n"));
+        assert_eq!(display.lines().count(), Config::OPTIONS.len() + 2);
+    }
+
     #[test]
     fn config_all() {
         let res = Config::parse_str(&Config::default(), "\
@@ -914,6 +1826,28 @@ mod tests {
             ;show-answer: false
             ;show-value: false
             ;show-quiz-info: false
+            ;penalty: 0.25
+            ;scoring: right-minus-wrong
+            ;numeric-tolerance: 5%
+            ;fuzzy: 2
+            ;normalize: true
+            ;typed: true
+            ;show-explanation: true
+            ;hint-penalty: 0.5
+            ;justified: true
+            ;ask-inverse: true
+            ;allow-review: true
+            ;repeat-missed: true
+            ;mastery: 3
+            ;pool: Geology
+            ;sample: 25
+            ;time-limit: 90s
+            ;seed: 12
+            ;title: Rocks: An Introduction
+            ;author: Jan Sonja
+            ;version: 1.2
+            ;description: Igneous, sedimentary, and metamorphic rocks
+            ;tags: geology, Rocks ,
         ").expect("all config options should parse");
 
         // NOTE: make sure these are all different from the default; we need to make sure they're
@@ -927,11 +1861,58 @@ mod tests {
             show_answer: false,
             show_value: false,
             show_quiz_info: false,
+            penalty: 0.25,
+            scoring: Scoring::RightMinusWrong,
+            numeric_tolerance: Tolerance::Relative(5.0),
+            fuzzy: 2,
+            normalize: true,
+            typed: true,
+            show_explanation: true,
+            hint_penalty: 0.5,
+            justified: true,
+            ask_inverse: true,
+            allow_review: true,
+            repeat_missed: true,
+            mastery: 3,
+            pool: Some("Geology".to_owned()),
+            sample: Some(25),
+            time_limit: Some(Duration::from_secs(90)),
+            seed: Some(12),
+            title: Some("Rocks: An Introduction".to_owned()),
+            author: Some("Jan Sonja".to_owned()),
+            version: Some("1.2".to_owned()),
+            description: Some("Igneous, sedimentary, and metamorphic rocks".to_owned()),
+            tags: Some("geology, Rocks ,".to_owned()),
         };
 
         assert_eq!(res, expected)
     }
 
+    #[test]
+    fn config_all_errors() {
+        let (res, errors) = Config::parse_str_all(&Config::default(), ";value: 2\n;not-an-option\ntutorial: false\n;show-value: false");
+
+        let expected = Config {
+            value: 2.0,
+            show_value: false,
+            ..Default::default()
+        };
+
+        assert_eq!(res, expected);
+        assert_eq!(errors, vec![
+            ConfigError {
+                kind: ConfigErrorKind::InvalidOption,
+                context: "notanoption".to_owned(),
+                lines_parsed: 1,
+            },
+            ConfigError {
+                kind: ConfigErrorKind::MissingDelimiter,
+                context: "tutorial: false".to_owned(),
+                lines_parsed: 2,
+            },
+        ])
+    }
+
     #[test]
     fn question_empty() {
         let res = Question::parse_str(&Config::default(), "").expect_err("empty question should err with only config");
@@ -947,7 +1928,7 @@ mod tests {
 
     #[test]
     fn question_only_config() {
-        let res = Question::parse_str(&Config::default(), ";value: 0\n;tutorial: false\n#comment\n").expect_err("config-only question should err with only config");
+        let res = Question::parse_str(&Config::default(), ";value: 0\n;show-value: false\n#comment\n").expect_err("config-only question should err with only config");
         
         let expected = QuestionError {
             kind: QuestionErrorKind::OnlyConfig,
@@ -1028,6 +2009,7 @@ mod tests {
             title: "question".to_owned(),
             config: Config::default(),
             answers: vec![Answer::Correct("answer".to_owned())],
+            ..Question::new()
         };
 
         assert_eq!(res, expected)
@@ -1045,6 +2027,7 @@ mod tests {
                 Answer::Incorrect("incorrect".to_owned()),
                 Answer::Incorrect("also incorrect".to_owned()),
             ],
+            ..Question::new()
         };
 
         assert_eq!(res, expected)
@@ -1062,6 +2045,171 @@ mod tests {
                 Answer::Incorrect("incorrect".to_owned()),
                 Answer::Correct("also correct".to_owned()),
             ],
+            ..Question::new()
+        };
+
+        assert_eq!(res, expected)
+    }
+
+    #[test]
+    fn question_numeric() {
+        let res = Question::parse_str(&Config::default(), "?question\n+= 9.81 ± 0.05 m/s^2\n-ten").expect("numeric question should parse");
+
+        let expected = Question {
+            title: "question".to_owned(),
+            config: Config::default(),
+            answers: vec![
+                Answer::Numeric("9.81 ± 0.05 m/s^2".parse().expect("numeric answer should parse")),
+                Answer::Incorrect("ten".to_owned()),
+            ],
+            ..Question::new()
+        };
+
+        assert_eq!(res, expected)
+    }
+
+    #[test]
+    fn question_invalid_numeric() {
+        let res = Question::parse_str(&Config::default(), "?question\n+= ten").expect_err("question with invalid numeric answer should err");
+
+        let expected = QuestionError {
+            kind: QuestionErrorKind::InvalidNumericAnswer("ten".to_owned()),
+            lines_parsed: 0,
+            context: "question".to_owned(),
+        };
+
+        assert_eq!(res, expected)
+    }
+
+    #[test]
+    fn question_regex() {
+        let res = Question::parse_str(&Config::default(), "?question\n+re /^colou?r$/").expect("regex question should parse");
+
+        let expected = Question {
+            title: "question".to_owned(),
+            config: Config::default(),
+            answers: vec![Answer::Regex("^colou?r$".parse().expect("regex should parse"))],
+            ..Question::new()
+        };
+
+        assert_eq!(res, expected)
+    }
+
+    #[test]
+    fn question_explanations() {
+        let res = Question::parse_str(&Config::default(), "\
+?question
++answer
+-distractor
+>- why the distractor
+is wrong
+-other distractor
+> the question's
+> explanation
+>- why the other distractor is wrong
+").expect("question with explanations should parse");
+
+        let expected = Question {
+            title: "question".to_owned(),
+            config: Config::default(),
+            answers: vec![
+                Answer::Correct("answer".to_owned()),
+                Answer::Incorrect("distractor".to_owned()),
+                Answer::Incorrect("other distractor".to_owned()),
+            ],
+            explanation: Some("the question's\nexplanation".to_owned()),
+            answer_explanations: BTreeMap::from([
+                (1, "why the distractor is wrong".to_owned()),
+                (2, "why the other distractor is wrong".to_owned()),
+            ]),
+            hints: vec![],
+        };
+
+        assert_eq!(res, expected);
+
+        let res = Question::parse_str(&Config::default(), "?question\n-distractor\n+answer\n> the question's explanation").expect("question with explanations should parse");
+        assert_eq!(res.explanation(), Some("the question's explanation"));
+        assert_eq!(res.answer_explanation(0), None);
+
+        let err = Question::parse_str(&Config::default(), "?question\n+answer\n>- why it's wrong").expect_err("nothing to explain");
+        assert_eq!(err.kind, QuestionErrorKind::UnexplainedAnswer);
+    }
+
+    #[test]
+    fn question_ids() {
+        let quiz: Quiz = "?Which one?\n+a\n-b\n\n?Which one?\n+b\n-a\n\n?Which   one?\n+ a\n-c\n-d".parse().expect("quiz should parse");
+
+        // questions that share a title are told apart by their answers...
+        assert_ne!(quiz.questions[0].id(), quiz.questions[1].id());
+        // ...but whitespace and incorrect answers don't matter
+        assert_eq!(quiz.questions[0].id(), quiz.questions[2].id());
+    }
+
+    #[test]
+    fn question_sample_needs_pool() {
+        assert!(Question::parse_str(&Config::default(), ";pool: rocks\n;sample: 2\n?question\n+answer").is_ok());
+
+        let err = Question::parse_str(&Config::default(), "# a comment\n;sample: 2\n?question\n+answer").expect_err("sample without a pool should err");
+        assert!(matches!(&err.kind, QuestionErrorKind::ConfigError(e) if e.kind == ConfigErrorKind::SampleWithoutPool));
+        assert_eq!(err.lines_parsed, 1);
+    }
+
+    #[test]
+    fn quiz_fingerprint() {
+        let quiz = |text: &str| text.parse::<Quiz>().expect("quiz should parse");
+        let fingerprint = quiz(";title: Rocks\n---\n?Hardest?\n+diamond\n-talc\n>- it's the softest\n\n?Granite is...\n+igneous\n~ it cooled from magma").fingerprint();
+
+        // comments and formatting don't matter
+        assert_eq!(quiz("# about rocks\n;title: Rocks\n---\n\n?Hardest?\n+ diamond\n- talc\n>- it's the softest\n\n\n# a comment\n?Granite is...\n+igneous\n~ it cooled from magma").fingerprint(), fingerprint);
+
+        // anything that changes the quiz does
+        for changed in [
+            ";title: Rocks!\n---\n?Hardest?\n+diamond\n-talc\n>- it's the softest\n\n?Granite is...\n+igneous\n~ it cooled from magma",
+            ";title: Rocks\n---\n?Hardest?\n+diamond\n-quartz\n>- it's the softest\n\n?Granite is...\n+igneous\n~ it cooled from magma",
+            ";title: Rocks\n---\n?Hardest?\n+diamond\n-talc\n> it's the softest\n\n?Granite is...\n+igneous\n~ it cooled from magma",
+            ";title: Rocks\n---\n?Hardest?\n+diamond\n-talc\n>- it's the softest\n\n;value: 2\n?Granite is...\n+igneous\n~ it cooled from magma",
+            ";title: Rocks\n---\n?Hardest?\n+diamond\n-talc\n>- it's the softest\n\n?Granite is...\n+igneous",
+        ] {
+            assert_ne!(quiz(changed).fingerprint(), fingerprint, "{changed}");
+        }
+    }
+
+    #[test]
+    fn question_hints() {
+        let res = Question::parse_str(&Config::default(), "?question\n~ first hint\n+answer\n-distractor\n~ second\nhint").expect("question with hints should parse");
+
+        let expected = Question {
+            title: "question".to_owned(),
+            config: Config::default(),
+            answers: vec![
+                Answer::Correct("answer".to_owned()),
+                Answer::Incorrect("distractor".to_owned()),
+            ],
+            hints: vec!["first hint".to_owned(), "second hint".to_owned()],
+            ..Question::new()
+        };
+
+        assert_eq!(res, expected);
+
+        // answers that only look like patterns are still literal
+        let res = Question::parse_str(&Config::default(), "?question\n+/etc/\n-/usr/bin/").expect("question should parse");
+        assert_eq!(res.answers, vec![Answer::Correct("/etc/".to_owned()), Answer::Incorrect("/usr/bin/".to_owned())]);
+
+        // patterns can't be choices
+        let err = Question::parse_str(&Config::default(), "?question\n+re /^a$/\n+b\n-c").expect_err("choice question with a pattern should err");
+        assert_eq!(err.kind, QuestionErrorKind::RegexInChoiceQuestion);
+        assert!(Question::parse_str(&Config::default(), ";typed: true\n?question\n+re /^a$/\n+b\n-c").is_ok());
+        assert!(Question::parse_str(&Config::default(), "?question\n+re /^colou?r$/\n+color").is_ok());
+    }
+
+    #[test]
+    fn question_invalid_regex() {
+        let res = Question::parse_str(&Config::default(), "?question\n+re /colo(u?r/").expect_err("question with invalid regex answer should err");
+
+        let expected = QuestionError {
+            kind: QuestionErrorKind::InvalidRegexAnswer("colo(u?r".to_owned(), "colo(u?r".parse::<Regex>().expect_err("regex shouldn't parse")),
+            lines_parsed: 0,
+            context: "question".to_owned(),
         };
 
         assert_eq!(res, expected)
@@ -1074,6 +2222,7 @@ mod tests {
         let expected = Quiz {
             config: Config::default(),
             questions: vec![],
+            sections: vec![],
             total_score: 0.0,
         };
 
@@ -1087,6 +2236,7 @@ mod tests {
         let expected = Quiz {
             config: Config::default(),
             questions: vec![],
+            sections: vec![],
             total_score: 0.0,
         };
 
@@ -1104,12 +2254,32 @@ mod tests {
                 ..Default::default()
             },
             questions: vec![],
+            sections: vec![],
             total_score: 0.0,
         };
 
         assert_eq!(res, expected)
     }
 
+    #[test]
+    fn quiz_metadata() {
+        let res: Quiz = ";title: Rocks\n;author: Jan Sonja\n;tags: geology, ,intro\n---\n?question\n+answer".parse().expect("quiz with metadata should parse");
+
+        let expected = Metadata {
+            title: Some("Rocks".to_owned()),
+            author: Some("Jan Sonja".to_owned()),
+            version: None,
+            description: None,
+            tags: vec!["geology".to_owned(), "intro".to_owned()],
+        };
+
+        assert_eq!(res.metadata(), expected);
+        assert_eq!(res.metadata().to_string(), "Title: Rocks\nAuthor: Jan Sonja\nTags: geology, intro\n");
+
+        let res: Quiz = "?question\n+answer".parse().expect("quiz should parse");
+        assert!(res.metadata().is_empty());
+    }
+
     #[test]
     fn quiz_single_question() {
         let res: Quiz = "?question\n+answer".parse().expect("quiz with questions should parse");
@@ -1120,7 +2290,9 @@ mod tests {
                 title: "question".to_owned(),
                 answers: vec![Answer::Correct("answer".to_owned())],
                 config: Config::default(),
+                ..Question::new()
             }],
+            sections: vec![],
             total_score: 1.0,
         };
 
@@ -1139,8 +2311,10 @@ mod tests {
                 config: Config {
                     value: 2.0,
                     ..Default::default()
-                }
+                },
+                ..Question::new()
             }],
+            sections: vec![],
             total_score: 2.0,
         };
 
@@ -1158,6 +2332,7 @@ mod tests {
                     title: "question".to_owned(),
                     answers: vec![Answer::Correct("answer".to_owned())],
                     config: Config::default(),
+                    ..Question::new()
                 },
                 Question {
                     title: "question2".to_owned(),
@@ -1166,8 +2341,10 @@ mod tests {
                         Answer::Incorrect("incorrect".to_owned()),
                     ],
                     config: Config::default(),
+                    ..Question::new()
                 },
             ],
+            sections: vec![],
             total_score: 2.0,
         };
 
@@ -1187,7 +2364,8 @@ mod tests {
                     config: Config {
                         ordered_answers: true,
                         ..Default::default()
-                    }
+                    },
+                    ..Question::new()
                 },
                 Question {
                     title: "question2".to_owned(),
@@ -1198,9 +2376,11 @@ mod tests {
                     config: Config {
                         value: 3.0,
                         ..Default::default()
-                    }
+                    },
+                    ..Question::new()
                 },
             ],
+            sections: vec![],
             total_score: 4.0,
         };
 
@@ -1224,7 +2404,8 @@ mod tests {
                         ordered_answers: true,
                         tutorial: false,
                         ..Default::default()
-                    }
+                    },
+                    ..Question::new()
                 },
                 Question {
                     title: "question2".to_owned(),
@@ -1236,9 +2417,11 @@ mod tests {
                         value: 3.0,
                         tutorial: false,
                         ..Default::default()
-                    }
+                    },
+                    ..Question::new()
                 },
             ],
+            sections: vec![],
             total_score: 4.0,
         };
 
@@ -1270,4 +2453,391 @@ mod tests {
 
         assert_eq!(res, expected)
     }
+
+    #[test]
+    fn quiz_all_errors() {
+        let res = Quiz::parse_all(";not-an-option\n---\n;value: x\n?question\n+answer\n\n?question2\n+answer\n\n?question3\n-incorrect")
+            .expect_err("quiz with multiple errors should err");
+
+        let lines: Vec<usize> = res.iter().map(|e| e.lines_parsed).collect();
+        assert_eq!(lines, vec![0, 2, 9]);
+
+        assert!(matches!(res[0].kind, QuizErrorKind::ConfigError(_)));
+        assert!(matches!(&res[1].kind, QuizErrorKind::QuestionError(e) if matches!(e.kind, QuestionErrorKind::ConfigError(_))));
+        assert!(matches!(&res[2].kind, QuizErrorKind::QuestionError(e) if e.kind == QuestionErrorKind::NoCorrectAnswer));
+    }
+
+    #[test]
+    fn quiz_sections() {
+        let quiz: Quiz = ";value: 3\n---\n?intro\n+answer\n\n## Part 1: Minerals\n# a comment\n;value: 2\n;case-sensitive: true\n?quartz\n+answer\n\n\
+            ;value: 5\n?feldspar\n+answer\n\n## Part 2\n\n?granite\n+answer".parse().expect("quiz with sections should parse");
+
+        assert_eq!(quiz.sections.len(), 2);
+        assert_eq!((quiz.sections[0].title(), quiz.sections[0].questions()), ("Part 1: Minerals", 1..3));
+        assert_eq!((quiz.sections[1].title(), quiz.sections[1].questions()), ("Part 2", 3..4));
+        assert_eq!((quiz.section_of(0), quiz.section_of(2), quiz.section_of(3)), (None, Some(0), Some(1)));
+
+        // sections are layered between the file and their questions
+        let config = |i: usize| (quiz.questions[i].config.value, quiz.questions[i].config.case_sensitive);
+        assert_eq!([config(0), config(1), config(2), config(3)], [(3.0, false), (2.0, true), (5.0, true), (3.0, false)]);
+        assert_eq!(quiz.total_score, 13.0);
+
+        let res = Quiz::parse_all("?question\n+answer\n\n## Part 1\n;tutorial: false\n;time-limit: 5m\n?q\n-only wrong")
+            .expect_err("quiz with invalid section should err");
+
+        let lines: Vec<usize> = res.iter().map(|e| e.lines_parsed).collect();
+        assert_eq!(lines, vec![4, 5, 6]);
+
+        assert!(matches!(&res[0].kind, QuizErrorKind::ConfigError(e) if e.kind == ConfigErrorKind::FileOnlyOption));
+        assert!(matches!(&res[1].kind, QuizErrorKind::ConfigError(e) if e.kind == ConfigErrorKind::NotForSections));
+        assert!(matches!(&res[2].kind, QuizErrorKind::QuestionError(e) if e.kind == QuestionErrorKind::NoCorrectAnswer));
+    }
+
+    #[test]
+    fn quiz_all_errors_first_matches_from_str() {
+        let input = ";tutorial:false\n---\n;ordered_answers:true\n?question\n+answer\n\n;value:3\nquestion2\n+answer\n-incorrect\n\n?question3";
+
+        let all = Quiz::parse_all(input).expect_err("quiz with invalid questions should err");
+        let first = input.parse::<Quiz>().expect_err("quiz with invalid questions should err");
+
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0], first);
+    }
+
+    /// Run `quiz` with `input` as the quiz-taker's keystrokes, returning the score and everything
+    /// that was written.
+    fn take_scripted(quiz: &Quiz, input: &str) -> (f32, String) {
+        let mut output = Vec::new();
+        let result = quiz.take_with(input.as_bytes(), &mut output).expect("in-memory quiz shouldn't fail");
+        (result.score, String::from_utf8(output).expect("quiz output should be utf-8"))
+    }
+
+    #[test]
+    fn take_typed_answer() {
+        let quiz: Quiz = ";tutorial: false\n;show-quiz-info: false\n---\n?question\n+Answer".parse().expect("quiz should parse");
+
+        let (score, output) = take_scripted(&quiz, "answer\n\n");
+        assert_eq!(score, 1.0);
+        assert!(output.contains("question"));
+        assert!(output.contains("Correct!"));
+
+        let (score, output) = take_scripted(&quiz, "wrong\n\n");
+        assert_eq!(score, 0.0);
+        assert!(output.contains("Correct Answer: 'Answer'"));
+    }
+
+    #[test]
+    fn take_last_line_is_answer() {
+        let quiz: Quiz = ";tutorial: false\n---\n?question\n+answer".parse().expect("quiz should parse");
+
+        let (score, _) = take_scripted(&quiz, "wrong\nanswer\n\n");
+        assert_eq!(score, 1.0);
+    }
+
+    #[test]
+    fn take_multiple_choice_and_answer() {
+        let quiz: Quiz = ";tutorial: false\n---\n?question\n+answer\n-incorrect\n\n;value: 2\n?question2\n-incorrect\n+answer\n+also correct"
+            .parse().expect("quiz should parse");
+
+        let (score, output) = take_scripted(&quiz, "0\n\n1, 2\n\n");
+        assert_eq!(score, 3.0);
+        assert!(output.contains("(0) answer"));
+        assert!(output.contains("[2] also correct"));
+        assert!(output.contains("Your score: 3/3 (100%)"));
+
+        let (score, output) = take_scripted(&quiz, "1\n\n1\n\n");
+        assert_eq!(score, 0.0);
+        assert!(output.contains("Correct Answer: 0"));
+        assert!(output.contains("Correct Answers: 1, 2"));
+    }
+
+    #[test]
+    fn take_out_of_input() {
+        let quiz: Quiz = ";tutorial: false\n---\n?question\n+answer\n\n?question2\n+answer\n-incorrect".parse().expect("quiz should parse");
+
+        let (score, output) = take_scripted(&quiz, "");
+        assert_eq!(score, 0.0);
+        assert!(output.contains("stopped at question 1 of 2"));
+        assert!(!output.contains("Quiz finished!"));
+
+        // the last line counts if the input ends without a blank line
+        let (score, output) = take_scripted(&quiz, "answer\n\n0");
+        assert_eq!(score, 2.0);
+        assert!(output.contains("Quiz finished!"));
+    }
+
+    #[test]
+    fn take_partial_credit() {
+        let quiz: Quiz = ";tutorial: false\n;scoring: proportional\n---\n?question\n+a\n+b\n-c\n-d".parse().expect("quiz should parse");
+
+        let (score, output) = take_scripted(&quiz, "0 2\n\n");
+        assert_eq!(score, 0.5);
+        assert!(output.contains("Partial credit: 0.5/1 pt(s)"));
+        assert!(output.contains("Correct Answers: 0, 1"));
+        assert!(output.contains("Your score: 0.5/1 (50%)"));
+    }
+
+    #[test]
+    fn format_points_trims() {
+        assert_eq!(format_points(2.0), "2");
+        assert_eq!(format_points(1.5), "1.5");
+        assert_eq!(format_points(2.0 / 3.0), "0.67");
+        assert_eq!(format_points(0.0), "0");
+    }
+
+    #[test]
+    fn format_duration_units() {
+        assert_eq!(format_duration(Duration::from_millis(42_500)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(90)), "1m 30s");
+        assert_eq!(format_duration(Duration::from_secs(2 * 60 * 60 + 5)), "2h 0m 5s");
+    }
+
+    #[test]
+    fn take_time_limits() {
+        // a question with no time at all can't be answered in time
+        let quiz: Quiz = ";tutorial: false\n---\n?question\n+answer\n\n;time-limit: 0s\n?question2\n+answer\n\n?question3\n+answer"
+            .parse().expect("quiz should parse");
+
+        let (score, output) = take_scripted(&quiz, "answer\n\nanswer\n\nanswer\n\n");
+        assert_eq!(score, 2.0);
+        assert_eq!(output.matches("Time left: 0s").count(), 1);
+        assert_eq!(output.matches("Time's up!").count(), 1);
+        assert!(!output.contains("Time taken"));
+
+        let quiz: Quiz = ";tutorial: false\n;time-limit: 0s\n---\n?question\n+answer\n\n?question2\n+answer".parse().expect("quiz should parse");
+
+        let (score, output) = take_scripted(&quiz, "answer\n\nanswer\n\n");
+        assert_eq!(score, 0.0);
+        assert!(output.contains("You have 0s to finish the quiz."));
+        assert!(output.contains("the remaining questions were left unanswered"));
+        assert!(!output.contains("question2"));
+        assert!(output.contains("Time taken: 0s"));
+    }
+
+    #[test]
+    fn take_autosaving_and_resume() {
+        let quiz: Quiz = ";tutorial: false\n---\n?question\n+answer\n\n?question2\n+answer\n\n?question3\n+answer".parse().expect("quiz should parse");
+
+        // the quiz-taker leaves after the first question
+        let mut session = QuizSession::with_seed(&quiz, 0);
+        let mut saved = None;
+        session.take_autosaving("answer\n\n".as_bytes(), Vec::new(), |attempt| {
+            saved.get_or_insert(attempt.clone());
+            Ok(())
+        }).expect("quiz should be taken");
+
+        let saved = saved.expect("attempt should be saved after the first answer");
+        assert_eq!(saved.answers.len(), 1);
+
+        let mut session = QuizSession::resume(&quiz, &saved).expect("attempt should resume");
+        let mut output = Vec::new();
+        let score = session.take("\nanswer\n\n".as_bytes(), &mut output).expect("quiz should be taken");
+        let output = String::from_utf8(output).expect("output should be utf-8");
+
+        assert_eq!(score, 2.0);
+        assert!(output.contains("Resuming your attempt at question 2 of 3 (score so far: 1)."));
+        assert!(!output.contains("question\n"));
+    }
+
+    #[test]
+    fn take_penalty() {
+        let quiz: Quiz = ";tutorial: false\n;penalty: 0.5\n---\n?question\n+answer\n-incorrect\n\n?question2\n+answer\n\n?question3\n+answer"
+            .parse().expect("quiz should parse");
+
+        // wrong, skipped, right
+        let (score, output) = take_scripted(&quiz, "1\n\n\nanswer\n\n");
+        assert_eq!(score, 0.5);
+        assert_eq!(output.matches("Penalty: -0.5 pt(s)").count(), 1);
+        assert!(output.contains("Your score: 0.5/3 (17%)"));
+
+        // all wrong, the total isn't affected by penalties
+        let (score, output) = take_scripted(&quiz, "1\n\nwrong\n\nwrong\n\n");
+        assert_eq!(score, -1.5);
+        assert!(output.contains("Your score: -1.5/3 (-50%)"));
+
+        // penalties are graded, but not shown, with the answers hidden
+        let hidden: Quiz = ";tutorial: false\n;penalty: 0.5\n;show-answer: false\n---\n?question\n+answer\n-incorrect"
+            .parse().expect("quiz should parse");
+        let (score, output) = take_scripted(&hidden, "1\n\n");
+        assert_eq!(score, -0.5);
+        assert!(!output.contains("Penalty"));
+    }
+
+    #[test]
+    fn take_explanations() {
+        let quiz: Quiz = ";tutorial: false\n---\n?question\n+answer\n-distractor\n>- why it's wrong\n+also\n> the explanation".parse().expect("quiz should parse");
+
+        let (_, output) = take_scripted(&quiz, "1\n\n");
+        assert!(output.contains("'distractor': why it's wrong"));
+        assert!(output.contains("Explanation: the explanation"));
+
+        let quiz: Quiz = ";tutorial: false\n;show-answer: false\n---\n?question\n+answer\n-distractor\n>- why it's wrong\n+also\n> the explanation".parse().expect("quiz should parse");
+        let (_, output) = take_scripted(&quiz, "1\n\n");
+        assert!(!output.contains("why it's wrong"));
+        assert!(!output.contains("Explanation:"));
+
+        let quiz: Quiz = ";tutorial: false\n;show-answer: false\n;show-explanation: true\n---\n?question\n+answer\n-distractor\n>- why it's wrong\n+also\n> the explanation".parse().expect("quiz should parse");
+        let (_, output) = take_scripted(&quiz, "1\n\n");
+        assert!(output.contains("'distractor': why it's wrong"));
+        assert!(output.contains("Explanation: the explanation"));
+        assert!(!output.contains("Correct Answer"));
+    }
+
+    #[test]
+    fn take_hints() {
+        let quiz: Quiz = ";tutorial: false\n;hint-penalty: 0.25\n---\n?question\n+answer\n~ starts with an a\n~ ends with an r".parse().expect("quiz should parse");
+
+        let (score, output) = take_scripted(&quiz, "?hint\n\n?hint\n\n?hint\n\nanswer\n\n");
+        assert_eq!(score, 0.5);
+        assert!(output.contains("2 hint(s) available"));
+        assert!(output.contains("each costs 25% of the points"));
+        assert!(output.contains("Hint: starts with an a"));
+        assert!(output.contains("Hint: ends with an r"));
+        assert!(output.contains("There are no more hints for this question."));
+        assert!(output.contains("Hints used: 2"));
+
+        let (score, output) = take_scripted(&quiz, "answer\n\n");
+        assert_eq!(score, 1.0);
+        assert!(!output.contains("Hints used"));
+    }
+
+    #[test]
+    fn take_justified_and_allow_review() {
+        let quiz: Quiz = ";tutorial: false\n;justified: true\n;allow-review: true\n---\n?question\n+answer".parse().expect("quiz should parse");
+
+        let mut session = QuizSession::with_seed(&quiz, 0);
+        let mut output = Vec::new();
+        let score = session.take("answr\n\nclose enough\n\ny\n\n".as_bytes(), &mut output).expect("quiz should be taken");
+        let output = String::from_utf8(output).expect("output should be utf-8");
+
+        assert_eq!(score, 1.0);
+        assert!(output.contains("Justify your answer: "));
+        assert!(output.contains("Mark your answer as correct anyway?"));
+        assert!(output.contains("Marked as correct for 1 pt(s)."));
+        assert_eq!(session.review_log().len(), 2);
+        assert_eq!(session.review_log()[0].to_string(), "[justified] question\n    answer: answr\n    justification: close enough");
+        assert_eq!(session.review_log()[1].to_string(), "[marked correct] question\n    answer: answr\n    correct answer: answer");
+
+        // correct answers can't be marked
+        let (_, output) = take_scripted(&quiz, "answer\n\nbecause\n\n");
+        assert!(!output.contains("Mark your answer as correct anyway?"));
+    }
+
+    #[test]
+    fn take_ask_inverse() {
+        let quiz: Quiz = ";tutorial: false\n---\n;ask-inverse: true\n?pona\n+good".parse().expect("quiz should parse");
+
+        let (score, output) = take_scripted(&quiz, "good\n\npona\n\n");
+        assert_eq!(score, 2.0);
+        assert!(output.contains("(Inverse of an earlier question) What was asked, given the answer?\ngood"));
+        assert!(output.contains("Your score: 2/2 (100%)"));
+    }
+
+    #[test]
+    fn take_practice() {
+        let quiz: Quiz = ";tutorial: false\n;repeat-missed: true\n;mastery: 1\n---\n?question\n+answer".parse().expect("quiz should parse");
+
+        let (score, output) = take_scripted(&quiz, "wrong\n\nwrong\n\nanswer\n\n");
+        assert_eq!(score, 0.0);
+        assert_eq!(output.matches("You'll see this question again later.").count(), 2);
+        assert_eq!(output.matches("(Again, since you missed it earlier)\nquestion").count(), 2);
+        assert!(output.contains("Your score: 0/1 (0%)"));
+        assert!(output.contains("Practice:\n    question: mastered after 3 tries (2 missed)"));
+
+        let (_, output) = take_scripted(&quiz, "answer\n\n");
+        assert!(!output.contains("Practice:"));
+
+        // running out of input stops the quiz, even while there are questions to repeat
+        let mut session = QuizSession::with_seed(&quiz, 0).practice();
+        let mut output = Vec::new();
+        session.take("wrong\n\n".as_bytes(), &mut output).expect("quiz should be taken");
+        let output = String::from_utf8(output).expect("output should be utf-8");
+
+        assert_eq!(output.matches("You'll see this question again later.").count(), 1);
+        assert!(output.contains("stopped at question 2 of 2"));
+        assert!(!session.is_finished());
+
+        // skipped questions aren't repeated
+        let (_, output) = take_scripted(&quiz, "\n");
+        assert!(!output.contains("You'll see this question again later."));
+        assert!(output.contains("Quiz finished!"));
+    }
+
+    #[test]
+    fn take_review() {
+        let quiz: Quiz = ";tutorial: false\n;show-quiz-info: false\n---\n?question\n-distractor\n>- why it's wrong\n+answer\n> the explanation\n\n\
+            ?question2\n+answer\n\n;show-answer: false\n?question3\n+secret\n> hidden explanation\n\n?question4\n+answer"
+            .parse().expect("quiz should parse");
+
+        let (_, output) = take_scripted(&quiz, "0\n\nanswer\n\nwrong\n\n\nmissed\n\n");
+        assert!(output.contains("Review your answers?"));
+        assert!(output.contains("\n1. question\n   Your Answer: distractor\n   Correct Answer: answer\n   Points: 0/1\n   'distractor': why it's wrong\n   Explanation: the explanation\n"));
+        assert!(output.contains("\n4. question4\n   Your Answer: (skipped)\n"));
+        assert!(!output.contains("2. question2"));
+        assert!(!output.contains("3. question3"));
+
+        // questions with `show-answer: false` keep their answers hidden
+        let (_, output) = take_scripted(&quiz, "0\n\nanswer\n\nwrong\n\n\nall\n\n");
+        assert!(output.contains("\n2. question2\n   Your Answer: answer\n   Correct Answer: answer\n   Points: 1/1\n"));
+        assert!(output.contains("\n3. question3\n   Your Answer: wrong\n\n4. question4"));
+        assert!(!output.contains("secret"));
+        assert!(!output.contains("hidden explanation"));
+
+        let (_, output) = take_scripted(&quiz, "1\n\nanswer\n\nsecret\n\nanswer\n\nm\n\n");
+        assert!(output.contains("No missed questions to review."));
+    }
+
+    #[test]
+    fn take_sections() {
+        let quiz: Quiz = ";tutorial: false\n;show-quiz-info: false\n---\n?intro\n+answer\n\n## Part 1: Minerals\n;value: 2\n?quartz\n+answer\n\n\
+            ?feldspar\n+answer\n\n## Part 2: Rocks\n\n?granite\n+answer"
+            .parse().expect("quiz should parse");
+
+        let (score, output) = take_scripted(&quiz, "answer\n\nanswer\n\nwrong\n\nanswer\n\n");
+        assert_eq!(score, 4.0);
+        assert_eq!(output.matches("--- Part 1: Minerals ---\n\nFor 2 pt(s):\nquartz").count(), 1);
+        assert_eq!(output.matches("--- Part 2: Rocks ---\n\nFor 1 pt(s):\ngranite").count(), 1);
+        assert!(output.contains("Your score: 4/6 (67%)\n    Part 1: Minerals: 2/4 (50%)\n    Part 2: Rocks: 1/1 (100%)\n"));
+
+        // sections worth nothing don't divide by zero
+        let quiz: Quiz = ";tutorial: false\n---\n?intro\n+answer\n\n## Warm-up\n;value: 0\n?practice\n+answer".parse().expect("quiz should parse");
+        let (_, output) = take_scripted(&quiz, "answer\n\nanswer\n\n");
+        assert!(output.contains("    Warm-up: 0/0 (0%)\n"));
+        assert!(!output.contains("NaN"));
+    }
+
+    #[test]
+    fn take_shows_metadata() {
+        let quiz: Quiz = ";tutorial: false\n;title: Rocks\n;version: 2\n---\n?question\n+answer".parse().expect("quiz should parse");
+
+        let (_, output) = take_scripted(&quiz, "answer\n\n");
+        assert!(output.starts_with("Title: Rocks\nVersion: 2\nTotal Points: 1\n"));
+    }
+
+    #[test]
+    fn take_typed_known_incorrect() {
+        let quiz: Quiz = ";tutorial: false\n---\n;typed: true\n?question\n+USA\n+United States\n-Canada".parse().expect("quiz should parse");
+
+        let (score, output) = take_scripted(&quiz, "canada\n\n");
+        assert_eq!(score, 0.0);
+        assert!(output.contains("'Canada' is a known incorrect answer."));
+        assert!(output.contains("Correct Answer: 'USA'"));
+
+        let (score, _) = take_scripted(&quiz, "United States\n\n");
+        assert_eq!(score, 1.0);
+
+        let quiz: Quiz = ";tutorial: false\n;show-answer: false\n---\n;typed: true\n?question\n+USA\n-Canada".parse().expect("quiz should parse");
+        let (_, output) = take_scripted(&quiz, "canada\n\n");
+        assert!(!output.contains("known incorrect"));
+    }
+
+    #[test]
+    fn take_fuzzy_typo() {
+        let quiz: Quiz = ";tutorial: false\n---\n;fuzzy: 1\n?good\n+pona".parse().expect("quiz should parse");
+
+        let (score, output) = take_scripted(&quiz, "poma\n\n");
+        assert_eq!(score, 1.0);
+        assert!(output.contains("Accepted with typo, correct spelling is 'pona'"));
+        assert!(output.contains("Correct!"));
+    }
 }
@@ -2,11 +2,96 @@ use std::io::{Write, Read, stdout, stdin};
 use std::fs;
 use std::env;
 use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use quiz_app::Quiz;
+use quiz_app::{Quiz, QuizSession, options};
+use quiz_app::attempt::SavedAttempt;
+use quiz_app::history::{self, HistoryEntry, Stats};
+use quiz_app::study::{self, StudyStore};
+use quiz_app::results::ResultFormat;
+
+/// What to do with the quiz
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Command {
+    #[default]
+    Take,
+    /// Show statistics about past attempts at the quiz
+    Stats,
+    /// Study the quiz's questions that are due for review
+    Study,
+}
+
+/// Command-line arguments
+#[derive(Debug, Default)]
+struct Args {
+    command: Command,
+    quiz_path: Option<String>,
+    /// Seed to shuffle the quiz with (overrides the quiz's `seed` option)
+    seed: Option<u64>,
+    /// Print the config option reference instead of taking a quiz
+    options: bool,
+    /// Continue the quiz's saved attempt instead of starting a new one
+    resume: bool,
+    /// Where to write the results of the attempt (and in what format)
+    results: Option<(String, ResultFormat)>,
+    /// Repeat every missed question until it's mastered
+    practice: bool,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+
+        while let Some(arg) = args.next() {
+            match &arg[..] {
+                "--seed" => {
+                    let seed = args.next().ok_or("`--seed` expects a value")?;
+                    parsed.seed = Some(seed.parse().map_err(|e| format!("invalid seed '{seed}': {e}"))?);
+                },
+                "--options" => parsed.options = true,
+                "stats" | "study" if parsed.quiz_path.is_none() && parsed.command == Command::Take => {
+                    parsed.command = if arg == "stats" { Command::Stats } else { Command::Study };
+                },
+                "--resume" => parsed.resume = true,
+                "--practice" => parsed.practice = true,
+                "--results" => {
+                    let path = args.next().ok_or("`--results` expects a file")?;
+                    let format = ResultFormat::from_path(&path).ok_or_else(|| format!("can't tell the format of '{path}', `--results` expects a .json or .csv file"))?;
+                    parsed.results = Some((path, format));
+                },
+                _ if arg.starts_with("--") => return Err(format!("unknown option '{arg}'")),
+                _ if parsed.quiz_path.is_none() => parsed.quiz_path = Some(arg),
+                _ => return Err(format!("unexpected argument '{arg}'")),
+            }
+        }
+
+        if parsed.resume && parsed.seed.is_some() {
+            return Err("`--seed` can't be used with `--resume` (attempts keep their order)".to_owned());
+        }
+
+        Ok(parsed)
+    }
+}
 
 fn main() -> ExitCode {
-    let quiz_path = match env::args().skip(1).next() {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}");
+            eprintln!("Usage: quiz-app [--seed <seed> | --resume] [--practice] [--results <out.json|out.csv>] <quiz>");
+            eprintln!("       quiz-app stats <quiz>");
+            eprintln!("       quiz-app study [--seed <seed>] [--practice] <quiz>");
+            eprintln!("       quiz-app --options");
+            return confirm_exit(ExitCode::FAILURE);
+        }
+    };
+
+    if args.options {
+        print!("{0}", options::reference());
+        return ExitCode::SUCCESS;
+    }
+
+    let quiz_path = match args.quiz_path {
         Some(path) => path,
         None => {
             eprintln!("Please launch the quiz application with the path to the quiz as the first argument!");
@@ -24,9 +109,13 @@ fn main() -> ExitCode {
         },
     };
 
-    println!("Taking Quiz: {quiz_path}");
+    match args.command {
+        Command::Take => println!("Taking Quiz: {quiz_path}"),
+        Command::Study => println!("Studying Quiz: {quiz_path}"),
+        Command::Stats => {},
+    }
 
-    let quiz_str = match fs::read_to_string(quiz_path) {
+    let quiz_str = match fs::read_to_string(&quiz_path) {
         Ok(s) => s,
         Err(e) => {
             eprintln!("Could not read quiz file: {e}");
@@ -34,23 +123,217 @@ fn main() -> ExitCode {
         }
     };
 
-    let quiz: Quiz = match quiz_str.parse() {
+    let quiz = match Quiz::parse_all(&quiz_str) {
         Ok(quiz) => quiz,
-        Err(e) => {
-            eprintln!("Could not parse quiz: {e}");
+        Err(errors) => {
+            eprintln!("Could not parse quiz, found {0} error(s):", errors.len());
+            for e in errors {
+                eprintln!("    {e}");
+            }
             return confirm_exit(ExitCode::FAILURE);
         }
     };
 
-    let _ = match quiz.take() {
-        Ok(score) => score,
+    // attempts are kept in the history by the quiz's full path, so they're found from anywhere
+    let history_key = fs::canonicalize(&quiz_path)
+        .map_or_else(|_| quiz_path.clone(), |path| path.to_string_lossy().into_owned());
+
+    match args.command {
+        Command::Take => {},
+        Command::Stats => return show_stats(&quiz, &history_key),
+        Command::Study => return study(&quiz, &history_key, args.seed, args.practice),
+    }
+
+    let attempt_path = format!("{quiz_path}.attempt");
+
+    let mut session = if args.resume {
+        match resume_attempt(&attempt_path, &quiz) {
+            Ok(session) => session,
+            Err(e) => {
+                eprintln!("Could not resume the attempt saved in {attempt_path}: {e}");
+                return confirm_exit(ExitCode::FAILURE);
+            }
+        }
+    } else {
+        if fs::exists(&attempt_path).unwrap_or(false) {
+            println!("There's an unfinished attempt at this quiz; launch with `--resume` to continue it (answering a question replaces it).");
+        }
+
+        let session = match args.seed {
+            Some(seed) => QuizSession::with_seed(&quiz, seed),
+            None => QuizSession::new(&quiz),
+        };
+
+        if args.practice { session.practice() } else { session }
+    };
+
+    // saved after every answer, so quitting partway through (even with Ctrl-C) keeps the attempt
+    let autosave = |attempt: &SavedAttempt| fs::write(&attempt_path, attempt.to_string());
+
+    match session.take_autosaving(stdin().lock(), stdout(), autosave) {
+        Ok(_) => {},
         Err(e) => {
             eprintln!("Could not take quiz: {e}");
+            if fs::exists(&attempt_path).unwrap_or(false) {
+                eprintln!("Your attempt was saved, launch with `--resume` to continue it.");
+            }
             return confirm_exit(ExitCode::FAILURE);
         }
     };
-    
-    return confirm_exit(ExitCode::SUCCESS);
+
+    if session.is_finished() {
+        // the attempt is finished, there's nothing left to resume
+        if let Err(e) = fs::remove_file(&attempt_path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            eprintln!("Could not remove saved attempt {attempt_path}: {e}");
+        }
+
+        let entry = HistoryEntry {
+            quiz: history_key,
+            quiz_hash: quiz.fingerprint(),
+            finished_at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            result: session.result(),
+        };
+
+        match history::default_path() {
+            Some(path) => if let Err(e) = history::append(&path, &entry) {
+                eprintln!("Could not save this attempt to your history ({0}): {e}", path.display());
+            },
+            None => eprintln!("Could not save this attempt to your history: couldn't find a place to keep it (set XDG_DATA_HOME)"),
+        }
+    } else if fs::exists(&attempt_path).unwrap_or(false) {
+        println!("Your attempt was saved, launch with `--resume` to continue it.");
+    }
+
+    if let Some((path, format)) = &args.results {
+        let mut results = Vec::new();
+        let written = session.result().write(*format, &mut results).and_then(|()| fs::write(path, results));
+
+        match written {
+            Ok(()) => println!("Results were written to {path}"),
+            Err(e) => eprintln!("Could not write results: {e}"),
+        }
+    }
+
+    if !session.review_log().is_empty() {
+        let log_path = format!("{quiz_path}.review.log");
+
+        match write_review_log(&log_path, &session) {
+            Ok(()) => println!("Answers to review were logged to {log_path}"),
+            Err(e) => eprintln!("Could not write review log: {e}"),
+        }
+    }
+
+    confirm_exit(ExitCode::SUCCESS)
+}
+
+/// Print statistics about the finished attempts at `quiz` in the history.
+fn show_stats(quiz: &Quiz, history_key: &str) -> ExitCode {
+    let Some(path) = history::default_path() else {
+        eprintln!("Could not find your history (set XDG_DATA_HOME)");
+        return ExitCode::FAILURE;
+    };
+
+    let entries = match history::read(&path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Could not read your history ({0}): {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let entries: Vec<HistoryEntry> = entries.into_iter().filter(|entry| entry.quiz == history_key).collect();
+
+    let metadata = quiz.metadata();
+    println!("Stats for {0}\n", metadata.title.as_deref().unwrap_or(history_key));
+    print!("{0}", Stats::new(&entries, quiz.fingerprint()));
+
+    ExitCode::SUCCESS
+}
+
+/// Ask the questions in `quiz` that are due for review, then reschedule them.
+fn study(quiz: &Quiz, history_key: &str, seed: Option<u64>, practice: bool) -> ExitCode {
+    let Some(path) = StudyStore::default_path() else {
+        eprintln!("Could not find your study schedules (set XDG_DATA_HOME)");
+        return confirm_exit(ExitCode::FAILURE);
+    };
+
+    let mut store = match StudyStore::read(&path) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("Could not read your study schedules ({0}): {e}", path.display());
+            return confirm_exit(ExitCode::FAILURE);
+        }
+    };
+
+    let now = || SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let started_at = now();
+
+    let session = match seed {
+        Some(seed) => QuizSession::with_seed(quiz, seed),
+        None => QuizSession::new(quiz),
+    };
+    let session = session.only(|_, question| store.state(history_key, &question.id()).is_due(started_at));
+    let mut session = if practice { session.practice() } else { session };
+
+    if session.is_finished() {
+        println!("Nothing is due for review right now, come back {0}.", due_in(store.next_due(history_key, quiz), started_at));
+        return confirm_exit(ExitCode::SUCCESS);
+    }
+
+    println!("{0} question(s) are due for review.", session.progress().1);
+
+    if let Err(e) = session.take(stdin().lock(), stdout()) {
+        eprintln!("Could not take quiz: {e}");
+        return confirm_exit(ExitCode::FAILURE);
+    }
+
+    store.review_results(history_key, quiz, &session.result(), now());
+    if let Err(e) = store.write(&path) {
+        eprintln!("Could not save your study schedules ({0}): {e}", path.display());
+        return confirm_exit(ExitCode::FAILURE);
+    }
+
+    println!("The next review is {0}.", due_in(store.next_due(history_key, quiz), now()));
+    confirm_exit(ExitCode::SUCCESS)
+}
+
+/// When something that's `due` is, relative to `now` (ex. `in 3 day(s)`).
+fn due_in(due: Option<u64>, now: u64) -> String {
+    match due {
+        Some(due) if due > now => format!("in {0} day(s)", (due - now).div_ceil(study::DAY)),
+        _ => "now".to_owned(),
+    }
+}
+
+/// Continue the attempt at `quiz` saved at `path`.
+fn resume_attempt<'a>(path: &str, quiz: &'a Quiz) -> Result<QuizSession<'a>, String> {
+    let saved = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let attempt: SavedAttempt = saved.parse().map_err(|e| format!("{e}"))?;
+
+    QuizSession::resume(quiz, &attempt).map_err(|e| e.to_string())
+}
+
+/// Append the session's justifications and self-marked answers to the log at `path`.
+fn write_review_log(path: &str, session: &QuizSession) -> std::io::Result<()> {
+    let mut log = fs::OpenOptions::new().create(true).append(true).open(path)?;
+
+    let finished_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    write!(log, "--- ")?;
+    if let Some(title) = session.quiz().config.title() {
+        write!(log, "{title}: ")?;
+    }
+    write!(log, "attempt finished at {finished_at} (unix time)")?;
+    if let Some(seed) = session.seed() {
+        write!(log, ", seed {seed}")?;
+    }
+    writeln!(log, " ---")?;
+
+    for entry in session.review_log() {
+        writeln!(log, "{entry}")?;
+    }
+    writeln!(log)
 }
 
 fn confirm_exit(code: ExitCode) -> ExitCode {
@@ -60,5 +343,5 @@ fn confirm_exit(code: ExitCode) -> ExitCode {
     // wait for ack, then exit
     _ = stdin().read(&mut[]);
 
-    return code;
+    code
 }
@@ -0,0 +1,218 @@
+//! Numeric answers for typed-answer questions.
+//!
+//! A numeric answer is written as `+= <number> [± <tolerance>[%]] [unit]` (`+-` and `+/-` work
+//! in place of `±`), e.g. `+= 9.81 ± 0.05 m/s^2`. The quiz-taker's answer is parsed as a number
+//! (so `1e3` and `1000` are the same) and is correct if it's within the tolerance of the answer.
+
+/// How far from the answer a numeric response can be and still be correct.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tolerance {
+    /// An absolute difference
+    Absolute(f64),
+    /// A difference relative to the answer, in percent
+    Relative(f64),
+}
+
+impl Tolerance {
+    fn allows(&self, answer: f64, response: f64) -> bool {
+        let allowed = match *self {
+            Self::Absolute(t) => t.abs(),
+            Self::Relative(p) => answer.abs() * p.abs() / 100.0,
+        };
+
+        // leave a little room for floating point error
+        (response - answer).abs() <= allowed + f64::EPSILON * answer.abs().max(1.0)
+    }
+}
+
+impl std::default::Default for Tolerance {
+    fn default() -> Self {
+        Self::Absolute(0.0)
+    }
+}
+
+impl std::str::FromStr for Tolerance {
+    type Err = std::num::ParseFloatError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().strip_suffix('%') {
+            Some(percent) => Ok(Self::Relative(percent.trim().parse()?)),
+            None => Ok(Self::Absolute(s.trim().parse()?)),
+        }
+    }
+}
+
+impl std::fmt::Display for Tolerance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Absolute(t) => write!(f, "{t}"),
+            Self::Relative(p) => write!(f, "{p}%"),
+        }
+    }
+}
+
+/// A correct answer that's a number (with an optional tolerance and unit).
+#[derive(Clone, Debug, PartialEq)]
+pub struct NumericAnswer {
+    /// The answer as written (after the `=`)
+    text: String,
+    value: f64,
+    /// The tolerance written with the answer (the question's `numeric-tolerance` if `None`)
+    tolerance: Option<Tolerance>,
+    unit: Option<String>,
+}
+
+impl NumericAnswer {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn tolerance(&self) -> Option<Tolerance> {
+        self.tolerance
+    }
+
+    pub fn unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+
+    /// Whether `response` is this answer (within `default_tolerance` if the answer doesn't have
+    /// its own tolerance).
+    ///
+    /// The response's unit is optional, but if it's given it must match the answer's.
+    pub fn matches(&self, response: &str, default_tolerance: Tolerance) -> bool {
+        let Some((value, unit)) = split_number(response.trim()) else {
+            return false;
+        };
+
+        let unit = normalize_unit(unit);
+        if !unit.is_empty() && Some(unit) != self.unit {
+            return false;
+        }
+
+        self.tolerance.unwrap_or(default_tolerance).allows(self.value, value)
+    }
+}
+
+/// An error parsing a `NumericAnswer`
+#[derive(Debug, PartialEq)]
+pub struct ParseNumericAnswerError;
+
+impl std::fmt::Display for ParseNumericAnswerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected `<number> [± <tolerance>[%]] [unit]`")
+    }
+}
+
+impl std::error::Error for ParseNumericAnswerError {}
+
+impl std::str::FromStr for NumericAnswer {
+    type Err = ParseNumericAnswerError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+
+        let (value, rest) = split_number(text).ok_or(ParseNumericAnswerError)?;
+        let rest = rest.trim_start();
+
+        let (tolerance, unit) = match ["±", "+/-", "+-"].iter().find_map(|sep| rest.strip_prefix(sep)) {
+            Some(tol) => {
+                let (t, rest) = split_number(tol.trim_start()).ok_or(ParseNumericAnswerError)?;
+
+                match rest.strip_prefix('%') {
+                    Some(rest) => (Some(Tolerance::Relative(t)), rest),
+                    None => (Some(Tolerance::Absolute(t)), rest),
+                }
+            },
+            None => (None, rest),
+        };
+
+        let unit = normalize_unit(unit);
+
+        Ok(Self {
+            text: text.to_owned(),
+            value,
+            tolerance,
+            unit: if unit.is_empty() { None } else { Some(unit) },
+        })
+    }
+}
+
+impl std::fmt::Display for NumericAnswer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{0}", self.text)
+    }
+}
+
+/// Split the longest number off the start of `s`, returning it and the rest of the string.
+fn split_number(s: &str) -> Option<(f64, &str)> {
+    let candidate_len = s
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')))
+        .unwrap_or(s.len());
+
+    // units can start with `e` (`eV`), so back off until the prefix is a number
+    (1..=candidate_len).rev()
+        .find_map(|len| s[..len].parse().ok().map(|n| (n, &s[len..])))
+}
+
+/// Units are compared without whitespace (`m / s` == `m/s`).
+fn normalize_unit(unit: &str) -> String {
+    unit.split_whitespace().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_numeric_answer() {
+        let res: NumericAnswer = "9.81 ± 0.05 m/s^2".parse().expect("numeric answer should parse");
+        assert_eq!(res, NumericAnswer {
+            text: "9.81 ± 0.05 m/s^2".to_owned(),
+            value: 9.81,
+            tolerance: Some(Tolerance::Absolute(0.05)),
+            unit: Some("m/s^2".to_owned()),
+        });
+
+        let res: NumericAnswer = "1e3 +- 1%".parse().expect("numeric answer should parse");
+        assert_eq!(res.value(), 1000.0);
+        assert_eq!(res.tolerance(), Some(Tolerance::Relative(1.0)));
+        assert_eq!(res.unit(), None);
+
+        let res: NumericAnswer = "5eV".parse().expect("numeric answer should parse");
+        assert_eq!(res.value(), 5.0);
+        assert_eq!(res.unit(), Some("eV"));
+    }
+
+    #[test]
+    fn parse_numeric_answer_error() {
+        for input in ["", "pi", "3 ±", "3 ± x"] {
+            assert_eq!(input.parse::<NumericAnswer>(), Err(ParseNumericAnswerError), "{input}");
+        }
+    }
+
+    #[test]
+    fn numeric_matches() {
+        let answer: NumericAnswer = "3.14".parse().expect("numeric answer should parse");
+        assert!(answer.matches("3.140", Tolerance::default()));
+        assert!(!answer.matches("3.15", Tolerance::default()));
+        assert!(answer.matches("3.15", Tolerance::Absolute(0.01)));
+        assert!(!answer.matches("three", Tolerance::Absolute(0.01)));
+
+        let answer: NumericAnswer = "1000".parse().expect("numeric answer should parse");
+        assert!(answer.matches("1e3", Tolerance::default()));
+        assert!(answer.matches("1010", "1%".parse().expect("tolerance should parse")));
+        assert!(!answer.matches("1011", "1%".parse().expect("tolerance should parse")));
+    }
+
+    #[test]
+    fn numeric_matches_units() {
+        let answer: NumericAnswer = "9.81 ± 0.05 m/s^2".parse().expect("numeric answer should parse");
+        assert!(answer.matches("9.8", Tolerance::default()));
+        assert!(answer.matches("9.8 m/s^2", Tolerance::default()));
+        assert!(answer.matches("9.8m / s^2", Tolerance::default()));
+        assert!(!answer.matches("9.8 kg", Tolerance::default()));
+        assert!(!answer.matches("9.7 m/s^2", Tolerance::default()));
+    }
+}
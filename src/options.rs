@@ -0,0 +1,436 @@
+//! The registry of config options.
+//!
+//! Every option is declared once, in the `config_options!` table in `lib.rs`, with its type,
+//! default, scope, and help text. Parsing, validation, `Display for Config`, and the option
+//! reference (`reference`) all come from that table, so adding an option is a one-line change.
+
+use std::time::Duration;
+
+use crate::{Config, ConfigValueParseError, Scoring};
+use crate::numeric::Tolerance;
+
+/// The type of value an option takes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OptionKind {
+    Bool,
+    Float,
+    Int,
+    String,
+    /// One of a fixed set of names
+    Enum(&'static [&'static str]),
+    /// A length of time, like `30s` or `10m`
+    Duration,
+    /// A numeric tolerance, like `0.01` or `1%`
+    Tolerance,
+}
+
+impl std::fmt::Display for OptionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool => write!(f, "bool"),
+            Self::Float => write!(f, "float"),
+            Self::Int => write!(f, "int"),
+            Self::String => write!(f, "string"),
+            Self::Enum(names) => write!(f, "{0}", names.join("/")),
+            Self::Duration => write!(f, "duration"),
+            Self::Tolerance => write!(f, "tolerance"),
+        }
+    }
+}
+
+/// Where an option can be set.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scope {
+    /// Only in the file-level config (it affects the quiz, not its questions)
+    File,
+    /// In the file-level config or on a question
+    Question,
+    /// In the file-level config for the whole quiz, or on a question for just that question
+    /// (questions don't inherit the file-level value)
+    NotInherited,
+}
+
+/// A config option, as declared in the `config_options!` table.
+pub struct ConfigOption {
+    /// The option's name, in kebab-case
+    pub name: &'static str,
+    pub kind: OptionKind,
+    pub scope: Scope,
+    pub help: &'static str,
+    /// Set the option from a (trimmed) value, or to its default if there's no value
+    pub(crate) set: fn(&mut Config, Option<&str>) -> Result<(), ConfigValueParseError>,
+    /// The option's value in a config, written the way it would be in a quiz
+    pub(crate) show: fn(&Config) -> String,
+}
+
+impl ConfigOption {
+    /// The option's value in `config`, written the way it would be in a quiz.
+    pub fn value(&self, config: &Config) -> String {
+        (self.show)(config)
+    }
+
+    pub fn default_value(&self) -> String {
+        (self.show)(&Config::default())
+    }
+
+    /// The default value as it's shown in the option reference (`none` for empty values).
+    fn default_label(&self) -> String {
+        let default = self.default_value();
+        if default.is_empty() { "none".to_owned() } else { default }
+    }
+}
+
+impl std::fmt::Debug for ConfigOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigOption")
+            .field("name", &self.name)
+            .field("kind", &self.kind)
+            .field("scope", &self.scope)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Option names ignore case, hyphens, underscores, and spaces (`case-sensitive` ==
+/// `Case_Sensitive`).
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.replace(['-', '_', ' '], "").to_lowercase()
+}
+
+/// Look up an option by (normalized) name.
+pub fn find(name: &str) -> Option<&'static ConfigOption> {
+    let name = normalize_name(name);
+    Config::OPTIONS.iter().find(|option| normalize_name(option.name) == name)
+}
+
+/// A reference of every option: its type, default, and what it does.
+pub fn reference() -> String {
+    let mut reference = String::new();
+
+    for (scope, heading) in [
+        (Scope::Question, "Options (for questions or the whole quiz):"),
+        (Scope::File, "File-level-only options (for the whole quiz):"),
+        (Scope::NotInherited, "Options for the whole quiz (in the file-level config) or a single question:"),
+    ] {
+        if !reference.is_empty() {
+            reference.push('\n');
+        }
+        reference.push_str(heading);
+        reference.push('\n');
+
+        for option in Config::OPTIONS.iter().filter(|option| option.scope == scope) {
+            reference.push_str(&format!("    {0}: {1} [{2}] - {3}\n",
+                option.name, option.kind, option.default_label(), option.help,
+            ));
+        }
+    }
+
+    reference
+}
+
+/// A type that can be the value of a config option.
+pub trait OptionValue: Sized {
+    const KIND: OptionKind;
+
+    fn parse_value(s: &str) -> Result<Self, ConfigValueParseError>;
+
+    /// The value, written the way it would be in a quiz
+    fn show_value(&self) -> String;
+}
+
+impl OptionValue for bool {
+    const KIND: OptionKind = OptionKind::Bool;
+
+    fn parse_value(s: &str) -> Result<Self, ConfigValueParseError> {
+        Ok(s.to_lowercase().parse()?)
+    }
+
+    fn show_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl OptionValue for f32 {
+    const KIND: OptionKind = OptionKind::Float;
+
+    fn parse_value(s: &str) -> Result<Self, ConfigValueParseError> {
+        Ok(s.parse()?)
+    }
+
+    fn show_value(&self) -> String {
+        format!("{self:?}") // keeps the `.0` so floats look like floats
+    }
+}
+
+impl OptionValue for usize {
+    const KIND: OptionKind = OptionKind::Int;
+
+    fn parse_value(s: &str) -> Result<Self, ConfigValueParseError> {
+        Ok(s.parse()?)
+    }
+
+    fn show_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Seeds: a number, or `random` for a different one every time
+impl OptionValue for Option<u64> {
+    const KIND: OptionKind = OptionKind::Int;
+
+    fn parse_value(s: &str) -> Result<Self, ConfigValueParseError> {
+        if s.eq_ignore_ascii_case("random") {
+            return Ok(None);
+        }
+
+        Ok(Some(s.parse()?))
+    }
+
+    fn show_value(&self) -> String {
+        self.map_or("random".to_owned(), |n| n.to_string())
+    }
+}
+
+impl OptionValue for String {
+    const KIND: OptionKind = OptionKind::String;
+
+    fn parse_value(s: &str) -> Result<Self, ConfigValueParseError> {
+        Ok(s.to_owned())
+    }
+
+    fn show_value(&self) -> String {
+        self.clone()
+    }
+}
+
+/// Counts that may be left out (`all`)
+impl OptionValue for Option<usize> {
+    const KIND: OptionKind = OptionKind::Int;
+
+    fn parse_value(s: &str) -> Result<Self, ConfigValueParseError> {
+        if s.is_empty() || s.eq_ignore_ascii_case("all") {
+            return Ok(None);
+        }
+
+        Ok(Some(s.parse()?))
+    }
+
+    fn show_value(&self) -> String {
+        self.map_or("all".to_owned(), |n| n.to_string())
+    }
+}
+
+/// Text that may be left out (`None` when empty)
+impl OptionValue for Option<String> {
+    const KIND: OptionKind = OptionKind::String;
+
+    fn parse_value(s: &str) -> Result<Self, ConfigValueParseError> {
+        Ok(if s.is_empty() { None } else { Some(s.to_owned()) })
+    }
+
+    fn show_value(&self) -> String {
+        self.clone().unwrap_or_default()
+    }
+}
+
+impl OptionValue for Scoring {
+    const KIND: OptionKind = OptionKind::Enum(Scoring::NAMES);
+
+    fn parse_value(s: &str) -> Result<Self, ConfigValueParseError> {
+        Ok(s.parse::<Scoring>()?)
+    }
+
+    fn show_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl OptionValue for Tolerance {
+    const KIND: OptionKind = OptionKind::Tolerance;
+
+    fn parse_value(s: &str) -> Result<Self, ConfigValueParseError> {
+        Ok(s.parse()?)
+    }
+
+    fn show_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl OptionValue for Duration {
+    const KIND: OptionKind = OptionKind::Duration;
+
+    fn parse_value(s: &str) -> Result<Self, ConfigValueParseError> {
+        Ok(parse_duration(s)?)
+    }
+
+    fn show_value(&self) -> String {
+        show_duration(*self)
+    }
+}
+
+/// Lengths of time that may be left out (`none`)
+impl OptionValue for Option<Duration> {
+    const KIND: OptionKind = OptionKind::Duration;
+
+    fn parse_value(s: &str) -> Result<Self, ConfigValueParseError> {
+        if s.is_empty() || s.eq_ignore_ascii_case("none") {
+            return Ok(None);
+        }
+
+        Ok(Some(parse_duration(s)?))
+    }
+
+    fn show_value(&self) -> String {
+        self.map_or("none".to_owned(), show_duration)
+    }
+}
+
+/// An error parsing a `Duration`
+#[derive(Debug, PartialEq)]
+pub struct ParseDurationError;
+
+impl std::fmt::Display for ParseDurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected a length of time like `30s`, `10m`, or `1h`")
+    }
+}
+
+impl std::error::Error for ParseDurationError {}
+
+/// Parse a length of time: a whole number followed by `s`, `m`, or `h` (seconds if there's no
+/// unit).
+pub fn parse_duration(s: &str) -> Result<Duration, ParseDurationError> {
+    let s = s.trim().to_lowercase();
+    let (number, seconds_per_unit) = match s.char_indices().last() {
+        Some((i, 's')) => (&s[..i], 1),
+        Some((i, 'm')) => (&s[..i], 60),
+        Some((i, 'h')) => (&s[..i], 60 * 60),
+        _ => (&s[..], 1),
+    };
+
+    let number: u64 = number.trim().parse().map_err(|_| ParseDurationError)?;
+    Ok(Duration::from_secs(number.checked_mul(seconds_per_unit).ok_or(ParseDurationError)?))
+}
+
+/// Write a length of time the way `parse_duration` reads it (in the largest whole unit).
+pub fn show_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+
+    match seconds {
+        0 => "0s".to_owned(),
+        _ if seconds.is_multiple_of(60 * 60) => format!("{0}h", seconds / (60 * 60)),
+        _ if seconds.is_multiple_of(60) => format!("{0}m", seconds / 60),
+        _ => format!("{seconds}s"),
+    }
+}
+
+/// Declare the `Config` struct and its option registry (`Config::OPTIONS`).
+///
+/// Each option is written `field: Type = default => "name", Scope, "help";`, with `Type`
+/// implementing `OptionValue`. The help is also the field's doc comment.
+macro_rules! config_options {
+    ($(
+        $field:ident: $ty:ty = $default:expr => $name:literal, $scope:ident, $help:literal;
+    )*) => {
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct Config {
+            $( #[doc = $help] $field: $ty, )*
+        }
+
+        impl std::default::Default for Config {
+            fn default() -> Self {
+                Self { $( $field: $default, )* }
+            }
+        }
+
+        impl Config {
+            /// Every config option, in the order they're displayed
+            pub const OPTIONS: &'static [$crate::options::ConfigOption] = &[$(
+                $crate::options::ConfigOption {
+                    name: $name,
+                    kind: <$ty as $crate::options::OptionValue>::KIND,
+                    scope: $crate::options::Scope::$scope,
+                    help: $help,
+                    set: |config, value| {
+                        config.$field = match value {
+                            Some(value) => <$ty as $crate::options::OptionValue>::parse_value(value)?,
+                            None => $default,
+                        };
+                        Ok(())
+                    },
+                    show: |config| $crate::options::OptionValue::show_value(&config.$field),
+                },
+            )*];
+        }
+    };
+}
+
+pub(crate) use config_options;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_options() {
+        assert_eq!(find("Case_Sensitive").map(|o| o.name), Some("case-sensitive"));
+        assert_eq!(find("tutorial").map(|o| o.scope), Some(Scope::File));
+        assert!(find("not-an-option").is_none());
+    }
+
+    #[test]
+    fn option_names_are_unique() {
+        for (i, option) in Config::OPTIONS.iter().enumerate() {
+            assert!(
+                Config::OPTIONS[i + 1..].iter().all(|other| normalize_name(other.name) != normalize_name(option.name)),
+                "{0} is declared twice", option.name,
+            );
+        }
+    }
+
+    #[test]
+    fn defaults_round_trip() {
+        let mut config = Config::default();
+
+        for option in Config::OPTIONS {
+            (option.set)(&mut config, Some(&option.default_value())).expect("default values should parse");
+        }
+
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn advanced_tutorial_documents_every_option() {
+        let tutorial = include_str!("../examples/advanced-tutorial.qz");
+
+        for option in Config::OPTIONS {
+            let entry = format!("# {0}: {1} [{2}] - ", option.name, option.kind, option.default_label());
+            assert!(tutorial.contains(&entry), "advanced tutorial is missing `{entry}`");
+        }
+    }
+
+    #[test]
+    fn reference_lists_every_option() {
+        let reference = reference();
+
+        for option in Config::OPTIONS {
+            assert!(reference.contains(&format!("    {0}: ", option.name)));
+        }
+        assert!(reference.contains("    seed: int [random] - "));
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_duration(" 2H "), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("45"), Ok(Duration::from_secs(45)));
+        assert_eq!(parse_duration("ten minutes"), Err(ParseDurationError));
+        assert_eq!(parse_duration("m"), Err(ParseDurationError));
+        assert_eq!(parse_duration("99999999999999999h"), Err(ParseDurationError));
+
+        assert_eq!(show_duration(Duration::from_secs(90)), "90s");
+        assert_eq!(show_duration(Duration::from_secs(600)), "10m");
+        assert_eq!(show_duration(Duration::from_secs(7200)), "2h");
+    }
+}
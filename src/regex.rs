@@ -0,0 +1,501 @@
+//! A small, dependency-free regular expression matcher for typed answers.
+//!
+//! Typed answers written as `+re /pattern/` are correct if the quiz-taker's answer matches the
+//! pattern. The supported syntax is the common subset of most regex flavors:
+//!
+//! - literals, `.` (any character), `^` and `$` (start/end of the answer)
+//! - `*`, `+`, `?`, `{n}`, `{n,}`, and `{n,m}` repetition (greedy)
+//! - `(...)` groups (`(?:...)` is accepted too) and `|` alternation
+//! - `[...]` and `[^...]` character classes with ranges (`[a-z]`)
+//! - `\d`, `\w`, `\s` (and their negations `\D`, `\W`, `\S`), `\n`, `\t`, and escaped
+//!   punctuation (`\.`, `\/`, etc.)
+//!
+//! Like most regex engines, a pattern matches anywhere in the answer unless it's anchored with
+//! `^` and `$`.
+//!
+//! Matching backtracks, so patterns like `(a+)+` can take exponentially long on some answers;
+//! rather than hang, the matcher gives up on a start in the answer after a fixed amount of work
+//! and moves on to the next one.
+
+use std::cell::Cell;
+
+/// How many steps matching from one start in the answer can take before giving up.
+const STEP_LIMIT: usize = 1_000_000;
+
+/// How deep matching can recurse before giving up (repeating a group recurses once per
+/// repetition).
+const DEPTH_LIMIT: usize = 2_000;
+
+/// A compiled regular expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regex {
+    /// The pattern as written
+    source: String,
+    node: Node,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Class {
+    items: Vec<ClassItem>,
+    negated: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ClassItem {
+    Range(char, char),
+    Shorthand(Shorthand, bool),
+}
+
+/// `\d`, `\w`, and `\s`
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Shorthand {
+    Digit,
+    Word,
+    Space,
+}
+
+impl Shorthand {
+    fn matches(self, c: char) -> bool {
+        match self {
+            Self::Digit => c.is_ascii_digit(),
+            Self::Word => c.is_alphanumeric() || c == '_',
+            Self::Space => c.is_whitespace(),
+        }
+    }
+}
+
+/// An error parsing a `Regex`
+#[derive(Debug, PartialEq)]
+pub struct ParseRegexError {
+    /// what's wrong with the pattern
+    reason: &'static str,
+}
+
+impl std::fmt::Display for ParseRegexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{0}", self.reason)
+    }
+}
+
+impl std::error::Error for ParseRegexError {}
+
+impl Regex {
+    /// The pattern as written.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the pattern matches anywhere in `text`.
+    pub fn is_match(&self, text: &str, case_sensitive: bool) -> bool {
+        let matcher = Matcher {
+            input: text.chars().collect(),
+            case_sensitive,
+            steps: Cell::new(0),
+            depth: Cell::new(0),
+        };
+
+        // each start gets its own budget, or a long answer could use it up before reaching a match
+        (0..=matcher.input.len()).any(|start| {
+            matcher.steps.set(0);
+            matcher.matches(&self.node, start, &mut |_| true)
+        })
+    }
+}
+
+impl std::str::FromStr for Regex {
+    type Err = ParseRegexError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { chars: s.chars().collect(), pos: 0 };
+
+        let node = parser.parse_alt()?;
+        if parser.pos < parser.chars.len() {
+            // the only thing that stops `parse_alt` early is a `)`
+            return Err(ParseRegexError { reason: "unmatched `)`" });
+        }
+
+        Ok(Self { source: s.to_owned(), node })
+    }
+}
+
+impl std::fmt::Display for Regex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "/{0}/", self.source)
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn next(&mut self) -> Result<char, ParseRegexError> {
+        let c = self.peek().ok_or(ParseRegexError { reason: "unexpected end of pattern" })?;
+        self.pos += 1;
+        Ok(c)
+    }
+
+    fn parse_alt(&mut self) -> Result<Node, ParseRegexError> {
+        let mut alts = vec![self.parse_concat()?];
+
+        while self.eat('|') {
+            alts.push(self.parse_concat()?);
+        }
+
+        Ok(if alts.len() == 1 { alts.swap_remove(0) } else { Node::Alt(alts) })
+    }
+
+    fn parse_concat(&mut self) -> Result<Node, ParseRegexError> {
+        let mut nodes = vec![];
+
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' { break; }
+
+            let atom = self.parse_atom()?;
+            nodes.push(self.parse_repeat(atom)?);
+        }
+
+        Ok(Node::Concat(nodes))
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, ParseRegexError> {
+        Ok(match self.next()? {
+            '(' => {
+                // non-capturing groups are the same thing here
+                if self.eat('?') && !self.eat(':') {
+                    return Err(ParseRegexError { reason: "unsupported group type" });
+                }
+
+                let node = self.parse_alt()?;
+                if !self.eat(')') {
+                    return Err(ParseRegexError { reason: "unclosed `(`" });
+                }
+                node
+            },
+            '[' => Node::Class(self.parse_class()?),
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '\\' => match self.parse_escape()? {
+                ClassItem::Range(c, _) => Node::Char(c),
+                item => Node::Class(Class { items: vec![item], negated: false }),
+            },
+            '*' | '+' | '?' | '{' => return Err(ParseRegexError { reason: "nothing to repeat" }),
+            c => Node::Char(c),
+        })
+    }
+
+    fn parse_repeat(&mut self, node: Node) -> Result<Node, ParseRegexError> {
+        let (min, max) = match self.peek() {
+            Some('*') => { self.pos += 1; (0, None) },
+            Some('+') => { self.pos += 1; (1, None) },
+            Some('?') => { self.pos += 1; (0, Some(1)) },
+            Some('{') => {
+                self.pos += 1;
+                let min = self.parse_number()?;
+                let max = if self.eat(',') {
+                    if self.peek() == Some('}') { None } else { Some(self.parse_number()?) }
+                } else {
+                    Some(min)
+                };
+
+                if !self.eat('}') || max.is_some_and(|max| max < min) {
+                    return Err(ParseRegexError { reason: "invalid repetition" });
+                }
+                (min, max)
+            },
+            _ => return Ok(node),
+        };
+
+        if matches!(self.peek(), Some('*' | '+' | '?' | '{')) {
+            return Err(ParseRegexError { reason: "nothing to repeat" });
+        }
+
+        Ok(Node::Repeat { node: Box::new(node), min, max })
+    }
+
+    fn parse_number(&mut self) -> Result<usize, ParseRegexError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        self.chars[start..self.pos].iter().collect::<String>().parse()
+            .map_err(|_| ParseRegexError { reason: "invalid repetition" })
+    }
+
+    /// Parse what comes after a `\`, as a class item (a single character is a one-character
+    /// range).
+    fn parse_escape(&mut self) -> Result<ClassItem, ParseRegexError> {
+        Ok(match self.next()? {
+            'd' => ClassItem::Shorthand(Shorthand::Digit, false),
+            'D' => ClassItem::Shorthand(Shorthand::Digit, true),
+            'w' => ClassItem::Shorthand(Shorthand::Word, false),
+            'W' => ClassItem::Shorthand(Shorthand::Word, true),
+            's' => ClassItem::Shorthand(Shorthand::Space, false),
+            'S' => ClassItem::Shorthand(Shorthand::Space, true),
+            'n' => ClassItem::Range('\n', '\n'),
+            't' => ClassItem::Range('\t', '\t'),
+            c if c.is_alphanumeric() => return Err(ParseRegexError { reason: "unsupported escape" }),
+            c => ClassItem::Range(c, c),
+        })
+    }
+
+    fn parse_class(&mut self) -> Result<Class, ParseRegexError> {
+        let negated = self.eat('^');
+        let mut items = vec![];
+
+        // a `]` right at the start is a literal
+        if self.eat(']') {
+            items.push(ClassItem::Range(']', ']'));
+        }
+
+        loop {
+            let item = match self.next().map_err(|_| ParseRegexError { reason: "unclosed `[`" })? {
+                ']' => break,
+                '\\' => self.parse_escape()?,
+                c => ClassItem::Range(c, c),
+            };
+
+            // ranges (a `-` at the end of the class is a literal)
+            match item {
+                ClassItem::Range(start, _) if self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|&c| c != ']') => {
+                    self.pos += 1;
+                    let end = match self.next()? {
+                        '\\' => match self.parse_escape()? {
+                            ClassItem::Range(c, _) => c,
+                            _ => return Err(ParseRegexError { reason: "invalid class range" }),
+                        },
+                        c => c,
+                    };
+
+                    if end < start {
+                        return Err(ParseRegexError { reason: "invalid class range" });
+                    }
+                    items.push(ClassItem::Range(start, end));
+                },
+                item => items.push(item),
+            }
+        }
+
+        Ok(Class { items, negated })
+    }
+}
+
+struct Matcher {
+    input: Vec<char>,
+    case_sensitive: bool,
+    /// Steps taken so far (`STEP_LIMIT` once matching has given up)
+    steps: Cell<usize>,
+    depth: Cell<usize>,
+}
+
+impl Matcher {
+    /// Every case-variant of `c` that should match (just `c` if case-sensitive).
+    fn variants(&self, c: char) -> Vec<char> {
+        if self.case_sensitive {
+            vec![c]
+        } else {
+            let mut variants = vec![c];
+            variants.extend(c.to_lowercase());
+            variants.extend(c.to_uppercase());
+            variants
+        }
+    }
+
+    fn class_matches(&self, class: &Class, c: char) -> bool {
+        let matched = self.variants(c).into_iter().any(|c| class.items.iter().any(|item| match *item {
+            ClassItem::Range(start, end) => (start..=end).contains(&c),
+            ClassItem::Shorthand(shorthand, negated) => shorthand.matches(c) != negated,
+        }));
+
+        matched != class.negated
+    }
+
+    /// Match `node` at `pos`, then call `k` with the position after the match (backtracking until
+    /// `k` accepts).
+    fn matches(&self, node: &Node, pos: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        if self.steps.get() >= STEP_LIMIT || self.depth.get() >= DEPTH_LIMIT {
+            // give up, all the way out
+            self.steps.set(STEP_LIMIT);
+            return false;
+        }
+        self.steps.set(self.steps.get() + 1);
+
+        self.depth.set(self.depth.get() + 1);
+        let matched = self.matches_node(node, pos, k);
+        self.depth.set(self.depth.get() - 1);
+
+        matched
+    }
+
+    fn matches_node(&self, node: &Node, pos: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        let current = self.input.get(pos).copied();
+
+        match node {
+            Node::Char(c) => current.is_some_and(|cur| self.variants(cur).contains(c)) && k(pos + 1),
+            Node::Any => current.is_some() && k(pos + 1),
+            Node::Class(class) => current.is_some_and(|cur| self.class_matches(class, cur)) && k(pos + 1),
+            Node::Start => pos == 0 && k(pos),
+            Node::End => pos == self.input.len() && k(pos),
+            Node::Concat(nodes) => self.matches_seq(nodes, pos, k),
+            Node::Alt(alts) => alts.iter().any(|alt| self.matches(alt, pos, k)),
+            Node::Repeat { node, min, max } if node.is_single_char() => self.matches_repeat_chars(node, *min, *max, pos, k),
+            Node::Repeat { node, min, max } => self.matches_repeat(node, *min, *max, 0, pos, k),
+        }
+    }
+
+    fn matches_seq(&self, nodes: &[Node], pos: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        match nodes.split_first() {
+            None => k(pos),
+            Some((first, rest)) => self.matches(first, pos, &mut |p| self.matches_seq(rest, p, k)),
+        }
+    }
+
+    /// Repeat a node that always matches exactly one character, without recursing per character:
+    /// match as many as possible, then back off one at a time.
+    fn matches_repeat_chars(&self, node: &Node, min: usize, max: Option<usize>, pos: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        let mut end = pos;
+        while max.is_none_or(|max| end - pos < max) && self.matches(node, end, &mut |_| true) {
+            end += 1;
+        }
+
+        (pos + min..=end).rev().any(|p| self.steps.get() < STEP_LIMIT && k(p))
+    }
+
+    fn matches_repeat(&self, node: &Node, min: usize, max: Option<usize>, count: usize, pos: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        // greedily try to match one more (as long as it makes progress, to avoid looping forever
+        // on things like `(a*)*`)
+        if max.is_none_or(|max| count < max)
+            && self.matches(node, pos, &mut |p| (p != pos || count < min) && self.matches_repeat(node, min, max, count + 1, p, k))
+        {
+            return true;
+        }
+
+        count >= min && k(pos)
+    }
+}
+
+impl Node {
+    /// Whether the node always matches exactly one character (when it matches).
+    fn is_single_char(&self) -> bool {
+        match self {
+            Self::Char(_) | Self::Any | Self::Class(_) => true,
+            Self::Concat(nodes) => nodes.len() == 1 && nodes[0].is_single_char(),
+            Self::Alt(alts) => alts.iter().all(Self::is_single_char),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_match(pattern: &str, text: &str) -> bool {
+        pattern.parse::<Regex>().expect("pattern should parse").is_match(text, true)
+    }
+
+    #[test]
+    fn regex_basics() {
+        assert!(is_match("^colou?r$", "color"));
+        assert!(is_match("^colou?r$", "colour"));
+        assert!(!is_match("^colou?r$", "colouur"));
+        assert!(!is_match("^colou?r$", "colors"));
+        assert!(is_match("colou?r", "colors"));
+        assert!(is_match("^a.c$", "abc"));
+        assert!(is_match("^$", ""));
+    }
+
+    #[test]
+    fn regex_repetition() {
+        assert!(is_match("^ab*c$", "ac"));
+        assert!(is_match("^ab+c$", "abbbc"));
+        assert!(!is_match("^ab+c$", "ac"));
+        assert!(is_match("^a{2,3}$", "aaa"));
+        assert!(!is_match("^a{2,3}$", "aaaa"));
+        assert!(is_match("^a{2}$", "aa"));
+        assert!(is_match("^a{2,}$", "aaaaa"));
+        assert!(is_match("^(a*)*b$", "aaab"));
+        assert!(is_match("^(a|b)*c$", "ababc"));
+        assert!(!is_match("^[ab]{2,3}$", "abab"));
+    }
+
+    #[test]
+    fn regex_long_answers() {
+        // greedy repeats don't recurse per character
+        let long = "a".repeat(200_000);
+        assert!(is_match("^.*x$", &format!("{long}x")));
+        assert!(!is_match("^.*x$", &long));
+        assert!(is_match("^a+$", &long));
+
+        // catastrophic backtracking gives up instead of hanging
+        let start = std::time::Instant::now();
+        assert!(!is_match("^(a+)+$", &format!("{0}b", "a".repeat(26))));
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+
+        // ...and so does repeating a group too many times
+        assert!(!is_match("^(ab)+$", &"ab".repeat(100_000)));
+        assert!(is_match("^(ab)+$", &"ab".repeat(500)));
+
+        // every start in the answer gets its own budget, so a match near the end is still found
+        assert!(is_match("a*c", &format!("{0}bc", "a".repeat(2_000))));
+    }
+
+    #[test]
+    fn regex_groups_and_classes() {
+        assert!(is_match("^(usa|united states)$", "united states"));
+        assert!(is_match("^(?:u\\.s\\.a\\.?)$", "u.s.a"));
+        assert!(is_match("^[a-c]+$", "abcabc"));
+        assert!(!is_match("^[^a-c]+$", "abd"));
+        assert!(is_match("^\\d{3}-\\d{4}$", "555-1234"));
+        assert!(is_match("^\\w+\\s\\w+$", "hello world"));
+        assert!(is_match("^[-a]+$", "a-a"));
+    }
+
+    #[test]
+    fn regex_case_insensitive() {
+        let regex: Regex = "^colou?r$".parse().expect("pattern should parse");
+        assert!(!regex.is_match("COLOR", true));
+        assert!(regex.is_match("COLOR", false));
+
+        let regex: Regex = "^[a-z]+$".parse().expect("pattern should parse");
+        assert!(regex.is_match("ABC", false));
+    }
+
+    #[test]
+    fn regex_errors() {
+        for pattern in ["(ab", "ab)", "[ab", "*a", "a**", "a{3,1}", "\\q", "[z-a]"] {
+            assert!(pattern.parse::<Regex>().is_err(), "{pattern}");
+        }
+    }
+}
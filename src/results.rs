@@ -0,0 +1,314 @@
+//! The results of an attempt at a quiz, and exporting them as JSON or CSV (for gradebooks and
+//! the like).
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crate::Metadata;
+use crate::json::Json;
+
+/// The results of an attempt at a quiz (see `QuizSession::result`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuizResult {
+    /// The quiz's title, author, and the like
+    pub metadata: Metadata,
+    pub seed: Option<u64>,
+    pub score: f32,
+    pub total_score: f32,
+    /// Whether the quiz's time limit ran out
+    pub timed_out: bool,
+    /// The questions answered, in the order they were asked
+    pub questions: Vec<QuestionResult>,
+}
+
+/// How the quiz-taker did on a question.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuestionResult {
+    /// Index of the question in `Quiz::questions`
+    pub index: usize,
+    /// What was asked (for inverse questions, the original question's answers)
+    pub title: String,
+    /// Whether this was the inverse of a question (see the `ask_inverse` option)
+    pub inverse: bool,
+    /// The quiz-taker's answer, empty if they skipped the question
+    pub answer: String,
+    pub correct_answers: Vec<String>,
+    pub points: f32,
+    /// The most points the question was worth
+    pub value: f32,
+    pub is_correct: bool,
+    pub skipped: bool,
+    pub timed_out: bool,
+    pub time_spent: Duration,
+    pub hints_used: usize,
+}
+
+impl QuestionResult {
+    pub fn used_hint(&self) -> bool {
+        self.hints_used > 0
+    }
+}
+
+/// The format to export a `QuizResult` in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResultFormat {
+    Json,
+    Csv,
+}
+
+impl ResultFormat {
+    /// The format for a file, going by its extension (`.json` or `.csv`).
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, extension) = path.rsplit_once('.')?;
+
+        match &extension.to_lowercase()[..] {
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+impl QuizResult {
+    /// The results as a single JSON value (see `QuizResult::from_json`).
+    pub(crate) fn to_json(&self) -> Json {
+        let metadata = &self.metadata;
+        let questions = self.questions.iter()
+            .map(|question| Json::Object(vec![
+                ("index".to_owned(), Json::number(question.index)),
+                ("title".to_owned(), Json::string(&question.title)),
+                ("inverse".to_owned(), Json::Bool(question.inverse)),
+                ("answer".to_owned(), Json::string(&question.answer)),
+                ("correct_answers".to_owned(), Json::Array(question.correct_answers.iter().map(Json::string).collect())),
+                ("points".to_owned(), Json::number(question.points)),
+                ("value".to_owned(), Json::number(question.value)),
+                ("correct".to_owned(), Json::Bool(question.is_correct)),
+                ("skipped".to_owned(), Json::Bool(question.skipped)),
+                ("timed_out".to_owned(), Json::Bool(question.timed_out)),
+                ("time_spent_secs".to_owned(), Json::number(seconds(question.time_spent))),
+                ("hints_used".to_owned(), Json::number(question.hints_used)),
+            ]))
+            .collect();
+
+        Json::Object(vec![
+            ("title".to_owned(), Json::option(metadata.title.as_deref())),
+            ("author".to_owned(), Json::option(metadata.author.as_deref())),
+            ("version".to_owned(), Json::option(metadata.version.as_deref())),
+            ("description".to_owned(), Json::option(metadata.description.as_deref())),
+            ("tags".to_owned(), Json::Array(metadata.tags.iter().map(Json::string).collect())),
+            ("seed".to_owned(), self.seed.map_or(Json::Null, Json::number)),
+            ("score".to_owned(), Json::number(self.score)),
+            ("total_score".to_owned(), Json::number(self.total_score)),
+            ("timed_out".to_owned(), Json::Bool(self.timed_out)),
+            ("questions".to_owned(), Json::Array(questions)),
+        ])
+    }
+
+    /// Read results written by `QuizResult::to_json`.
+    pub(crate) fn from_json(json: &Json) -> Option<Self> {
+        let string = |json: &Json, key| json.get(key).and_then(Json::as_str).map(str::to_owned);
+        let option = |key| json.get(key).and_then(Json::as_option).map(|s| s.map(str::to_owned));
+        let strings = |json: &Json, key| json.get(key)?.as_array()?.iter()
+            .map(|item| item.as_str().map(str::to_owned))
+            .collect::<Option<Vec<_>>>();
+
+        let questions = json.get("questions")?.as_array()?.iter()
+            .map(|question| Some(QuestionResult {
+                index: question.get("index")?.as_number()?,
+                title: string(question, "title")?,
+                inverse: question.get("inverse")?.as_bool()?,
+                answer: string(question, "answer")?,
+                correct_answers: strings(question, "correct_answers")?,
+                points: question.get("points")?.as_number()?,
+                value: question.get("value")?.as_number()?,
+                is_correct: question.get("correct")?.as_bool()?,
+                skipped: question.get("skipped")?.as_bool()?,
+                timed_out: question.get("timed_out")?.as_bool()?,
+                time_spent: Duration::try_from_secs_f64(question.get("time_spent_secs")?.as_number()?).ok()?,
+                hints_used: question.get("hints_used")?.as_number()?,
+            }))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            metadata: Metadata {
+                title: option("title")?,
+                author: option("author")?,
+                version: option("version")?,
+                description: option("description")?,
+                tags: strings(json, "tags")?,
+            },
+            seed: match json.get("seed")? {
+                Json::Null => None,
+                seed => Some(seed.as_number()?),
+            },
+            score: json.get("score")?.as_number()?,
+            total_score: json.get("total_score")?.as_number()?,
+            timed_out: json.get("timed_out")?.as_bool()?,
+            questions,
+        })
+    }
+
+    pub fn write<W: Write>(&self, format: ResultFormat, output: W) -> io::Result<()> {
+        match format {
+            ResultFormat::Json => self.write_json(output),
+            ResultFormat::Csv => self.write_csv(output),
+        }
+    }
+
+    /// Write the results as a (pretty-printed) JSON object.
+    pub fn write_json<W: Write>(&self, mut output: W) -> io::Result<()> {
+        writeln!(output, "{0:#}", self.to_json())
+    }
+
+    /// Write the results as CSV: a row per question, then a `total` row with the score.
+    ///
+    /// The quiz's title and version are repeated on every row so results from different quizzes
+    /// can be imported into the same sheet.
+    pub fn write_csv<W: Write>(&self, mut output: W) -> io::Result<()> {
+        let quiz_title = self.metadata.title.as_deref().unwrap_or("");
+        let quiz_version = self.metadata.version.as_deref().unwrap_or("");
+
+        writeln!(output, "quiz,version,question,title,answer,correct_answers,points,value,correct,skipped,timed_out,time_spent_secs,hints_used")?;
+
+        for question in &self.questions {
+            let row = [
+                csv_field(quiz_title),
+                csv_field(quiz_version),
+                question.index.to_string(),
+                csv_field(&question.title),
+                csv_field(&question.answer),
+                csv_field(&question.correct_answers.join("; ")),
+                question.points.to_string(),
+                question.value.to_string(),
+                question.is_correct.to_string(),
+                question.skipped.to_string(),
+                question.timed_out.to_string(),
+                seconds(question.time_spent).to_string(),
+                question.hints_used.to_string(),
+            ];
+            writeln!(output, "{0}", row.join(","))?;
+        }
+
+        let time_spent: Duration = self.questions.iter().map(|question| question.time_spent).sum();
+        let hints_used: usize = self.questions.iter().map(|question| question.hints_used).sum();
+        writeln!(output, "{0},{1},total,,,,{2},{3},,,{4},{5},{6}",
+            csv_field(quiz_title),
+            csv_field(quiz_version),
+            self.score,
+            self.total_score,
+            self.timed_out,
+            seconds(time_spent),
+            hints_used,
+        )
+    }
+}
+
+/// A length of time in seconds, to the millisecond.
+fn seconds(duration: Duration) -> f64 {
+    duration.as_millis() as f64 / 1000.0
+}
+
+/// Quote a CSV field if it needs it (doubling any quotes in it).
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) || s.trim() != s {
+        format!("\"{0}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> QuizResult {
+        QuizResult {
+            metadata: Metadata {
+                title: Some("Rocks".to_owned()),
+                version: Some("1.2".to_owned()),
+                tags: vec!["geology".to_owned()],
+                ..Default::default()
+            },
+            seed: Some(3),
+            score: 1.5,
+            total_score: 3.0,
+            timed_out: false,
+            questions: vec![
+                QuestionResult {
+                    index: 1,
+                    title: "Which is \"hardest\"?".to_owned(),
+                    inverse: false,
+                    answer: "Diamond".to_owned(),
+                    correct_answers: vec!["Diamond".to_owned()],
+                    points: 1.5,
+                    value: 2.0,
+                    is_correct: true,
+                    skipped: false,
+                    timed_out: false,
+                    time_spent: Duration::from_millis(4500),
+                    hints_used: 1,
+                },
+                QuestionResult {
+                    index: 0,
+                    title: "Name two, igneous rocks".to_owned(),
+                    inverse: false,
+                    answer: String::new(),
+                    correct_answers: vec!["Granite".to_owned(), "Basalt".to_owned()],
+                    points: 0.0,
+                    value: 1.0,
+                    is_correct: false,
+                    skipped: true,
+                    timed_out: false,
+                    time_spent: Duration::from_secs(2),
+                    hints_used: 0,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn results_json() {
+        let mut output = Vec::new();
+        example().write_json(&mut output).expect("writing to memory shouldn't fail");
+        let output = String::from_utf8(output).expect("output should be utf-8");
+
+        assert!(output.starts_with("{\n  \"title\": \"Rocks\",\n  \"author\": null,\n"));
+        assert!(output.contains("\"tags\": [\n    \"geology\"\n  ],\n  \"seed\": 3,\n  \"score\": 1.5,\n  \"total_score\": 3,\n"));
+        assert!(output.contains("\n    {\n      \"index\": 1,\n      \"title\": \"Which is \\\"hardest\\\"?\",\n"));
+        assert!(output.contains("\"correct_answers\": [\n        \"Diamond\"\n      ],\n      \"points\": 1.5,\n"));
+        assert!(output.ends_with("\"hints_used\": 0\n    }\n  ]\n}\n"));
+
+        // it's the same JSON that's read back
+        let json: Json = output.parse().expect("json should parse");
+        assert_eq!(QuizResult::from_json(&json), Some(example()));
+    }
+
+    #[test]
+    fn results_csv() {
+        let mut output = Vec::new();
+        example().write_csv(&mut output).expect("writing to memory shouldn't fail");
+        let output = String::from_utf8(output).expect("output should be utf-8");
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("quiz,version,question,title,answer,"));
+        assert_eq!(lines[1], "Rocks,1.2,1,\"Which is \"\"hardest\"\"?\",Diamond,Diamond,1.5,2,true,false,false,4.5,1");
+        assert_eq!(lines[2], "Rocks,1.2,0,\"Name two, igneous rocks\",,Granite; Basalt,0,1,false,true,false,2,0");
+        assert_eq!(lines[3], "Rocks,1.2,total,,,,1.5,3,,,false,6.5,1");
+    }
+
+    #[test]
+    fn results_json_round_trips() {
+        let result = example();
+        let json: Json = result.to_json().to_string().parse().expect("json should parse");
+        assert_eq!(QuizResult::from_json(&json), Some(result));
+    }
+
+    #[test]
+    fn result_formats() {
+        assert_eq!(ResultFormat::from_path("out.json"), Some(ResultFormat::Json));
+        assert_eq!(ResultFormat::from_path("results/Out.CSV"), Some(ResultFormat::Csv));
+        assert_eq!(ResultFormat::from_path("out.txt"), None);
+        assert_eq!(ResultFormat::from_path("out"), None);
+    }
+}
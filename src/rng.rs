@@ -0,0 +1,152 @@
+//! Seedable random number generation for shuffling questions and answers.
+//!
+//! Everything random about an attempt at a quiz is drawn from a single `Rng`, so an attempt can
+//! be replayed exactly by reusing its seed.
+
+use std::hash::{BuildHasher, Hasher, RandomState};
+
+/// A small pseudo-random number generator.
+pub trait Rng {
+    /// The next random `u64` in the sequence.
+    fn next_u64(&mut self) -> u64;
+
+    /// A uniformly-distributed random number in `0..n`.
+    ///
+    /// `n` must not be 0.
+    fn below(&mut self, n: usize) -> usize {
+        let n = n as u64;
+
+        // reject the values that would make the distribution uneven (the top partial range)
+        let zone = u64::MAX - (u64::MAX % n);
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return (x % n) as usize;
+            }
+        }
+    }
+
+    /// A uniformly-distributed random float in `0.0..1.0`.
+    fn next_f64(&mut self) -> f64 {
+        // 53 bits of precision (the size of an f64 mantissa)
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// The default `Rng`: SplitMix64.
+///
+/// It's tiny, fast, and passes the usual statistical tests, which is plenty for shuffling.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DefaultRng {
+    state: u64,
+}
+
+impl DefaultRng {
+    pub fn seed_from_u64(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl Rng for DefaultRng {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// A fresh, unpredictable seed.
+pub fn random_seed() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// Randomly shuffle `vec` in place (Fisher-Yates).
+pub fn shuffle<T, R: Rng + ?Sized>(vec: &mut [T], rng: &mut R) {
+    let n = vec.len();
+    if n == 0 { return }
+    for i in 0..(n - 1) {
+        let j = rng.below(n - i) + i;
+        vec.swap(i, j);
+    }
+}
+
+/// Randomly keep `n` of `items` (or all of them, if there aren't more than `n`), leaving the ones
+/// kept in their original order.
+pub fn sample<T, R: Rng + ?Sized>(items: &mut Vec<T>, n: usize, rng: &mut R) {
+    if items.len() <= n { return }
+
+    let mut picked: Vec<usize> = (0..items.len()).collect();
+    shuffle(&mut picked, rng);
+
+    let mut keep = vec![false; items.len()];
+    for &i in &picked[..n] {
+        keep[i] = true;
+    }
+
+    let mut keep = keep.into_iter();
+    items.retain(|_| keep.next().unwrap_or(false));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = DefaultRng::seed_from_u64(42);
+        let mut b = DefaultRng::seed_from_u64(42);
+
+        for _ in 0..16 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn below_in_range() {
+        let mut rng = DefaultRng::seed_from_u64(7);
+
+        for n in 1..50 {
+            assert!(rng.below(n) < n);
+        }
+    }
+
+    #[test]
+    fn next_f64_in_range() {
+        let mut rng = DefaultRng::seed_from_u64(7);
+
+        for _ in 0..100 {
+            let x = rng.next_f64();
+            assert!((0.0..1.0).contains(&x));
+        }
+    }
+
+    #[test]
+    fn shuffle_is_seeded_permutation() {
+        let mut a: Vec<usize> = (0..20).collect();
+        let mut b = a.clone();
+
+        shuffle(&mut a, &mut DefaultRng::seed_from_u64(3));
+        shuffle(&mut b, &mut DefaultRng::seed_from_u64(3));
+        assert_eq!(a, b);
+
+        let mut sorted = a.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn sample_keeps_order() {
+        let mut items: Vec<usize> = (0..20).collect();
+        sample(&mut items, 5, &mut DefaultRng::seed_from_u64(3));
+
+        assert_eq!(items.len(), 5);
+        assert!(items.is_sorted());
+
+        let mut few = vec![1, 2];
+        sample(&mut few, 5, &mut DefaultRng::seed_from_u64(3));
+        assert_eq!(few, [1, 2]);
+    }
+}
@@ -0,0 +1,1868 @@
+//! A step-by-step, frontend-agnostic way to take a `Quiz`.
+//!
+//! `QuizSession` holds all of the ordering, shuffling, grading, and scoring logic for an attempt
+//! at a quiz. Frontends (like `Quiz::take`) only have to show the current question, collect a
+//! `Response`, and show the `Feedback` that comes back from `QuizSession::submit`.
+
+use std::cmp;
+use std::time::Duration;
+
+use crate::{Answer, Config, Question, Quiz, Scoring, fuzzy};
+use crate::attempt::{ResumeError, SavedAnswer, SavedAttempt, SavedQuestion};
+use crate::clock::{Clock, SharedClock};
+use crate::results::{QuestionResult, QuizResult};
+use crate::rng::{self, DefaultRng, Rng};
+
+/// What kind of answer a question expects.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuestionKind {
+    /// The quiz-taker types the answer
+    Typed,
+    /// The quiz-taker picks the one correct option
+    SingleChoice,
+    /// The quiz-taker picks every correct option
+    MultipleChoice,
+}
+
+/// A quiz-taker's answer to a question (or the correct answer to one).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Response {
+    /// A typed answer
+    Text(String),
+    /// The indices of the chosen options, as they were presented
+    Choices(Vec<usize>),
+}
+
+impl Response {
+    /// Whether the response is empty (the question was skipped).
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Text(text) => text.trim().is_empty(),
+            Self::Choices(choices) => choices.is_empty(),
+        }
+    }
+}
+
+/// The result of submitting a `Response` to a `QuizSession`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Feedback {
+    /// Whether the response was graded as correct
+    pub is_correct: bool,
+    /// Whether the question was skipped (submitted with an empty response)
+    pub skipped: bool,
+    /// Index (into `Question::answers`) of the answer a typed response matched, if any
+    pub matched_answer: Option<usize>,
+    /// Whether a typed response was only accepted as a typo of `matched_answer` (see the `fuzzy`
+    /// option)
+    pub accepted_with_typo: bool,
+    /// Indices (into `Question::answers`) of the answers the response picked: the chosen options,
+    /// or `matched_answer` for typed responses
+    pub selected_answers: Vec<usize>,
+    /// How many of the question's hints were used before answering
+    pub hints_used: usize,
+    /// Whether time ran out before the question was answered (see the `time_limit` option), in
+    /// which case it's graded as skipped
+    pub timed_out: bool,
+    /// How long the quiz-taker spent on the question (up to its time limit)
+    pub time_spent: Duration,
+    /// The points awarded for the response
+    ///
+    /// Negative if the question has a `penalty` and the response earned nothing.
+    pub points: f32,
+    /// The response that would have been correct
+    pub correct_response: Response,
+    /// Whether the question will be asked again later, since it hasn't been mastered yet (see
+    /// `QuizSession::practice`)
+    pub will_repeat: bool,
+}
+
+/// The points earned in a section of the quiz (see `QuizSession::section_scores`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SectionScore {
+    /// Index of the section in `Quiz::sections`
+    pub section: usize,
+    pub score: f32,
+    /// The maximum score of the section's questions being asked
+    pub total_score: f32,
+}
+
+impl SectionScore {
+    /// The score as a percentage of the total (0 if the section isn't worth anything).
+    pub fn percent(&self) -> f32 {
+        if self.total_score == 0.0 { 0.0 } else { self.score * 100.0 / self.total_score }
+    }
+}
+
+/// How a question that was missed while practicing is coming along (see
+/// `QuizSession::practice`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mastery {
+    /// Index of the question in `Quiz::questions`
+    pub question: usize,
+    /// The question as it was asked
+    pub title: String,
+    /// Whether this is the inverse of the question (see the `ask_inverse` option)
+    pub inverse: bool,
+    /// How many times the question was asked
+    pub attempts: usize,
+    /// How many of those were missed
+    pub misses: usize,
+    /// Correct answers in a row since the last miss
+    pub streak: usize,
+    /// Correct answers in a row it takes to master the question (the `mastery` option)
+    pub required: usize,
+}
+
+impl Mastery {
+    pub fn is_mastered(&self) -> bool {
+        self.streak >= self.required
+    }
+}
+
+/// Something the quiz-taker did that a person should look over (see `QuizSession::review_log`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReviewEntry {
+    /// Index of the question in `Quiz::questions`
+    pub question: usize,
+    /// The question as it was asked
+    pub title: String,
+    /// The quiz-taker's answer
+    pub answer: String,
+    pub kind: ReviewKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReviewKind {
+    /// The quiz-taker explained their answer (the `justified` option)
+    Justification(String),
+    /// The quiz-taker marked their incorrect answer as correct (the `allow_review` option)
+    MarkedCorrect { correct_answer: String },
+}
+
+impl std::fmt::Display for ReviewEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ReviewKind::Justification(justification) => write!(f, "\
+                [justified] {0}\n    \
+                answer: {1}\n    \
+                justification: {2}",
+                self.title, self.answer, justification,
+            ),
+            ReviewKind::MarkedCorrect { correct_answer } => write!(f, "\
+                [marked correct] {0}\n    \
+                answer: {1}\n    \
+                correct answer: {2}",
+                self.title, self.answer, correct_answer,
+            ),
+        }
+    }
+}
+
+/// A question as it's presented in a `QuizSession` (with its answers in presentation order).
+#[derive(Clone, Debug, PartialEq)]
+pub struct AskedQuestion<'a> {
+    /// Index of the question in `Quiz::questions`
+    index: usize,
+    question: &'a Question,
+    /// Indices into the question's answers, in the order they're presented
+    answer_order: Vec<usize>,
+    /// For the inverse of a question (see the `ask_inverse` option), the question's correct
+    /// answers, which are asked in place of its title (the title is the answer)
+    inverse: Option<String>,
+    /// Whether the question is being asked again since it was missed earlier
+    repeat: bool,
+}
+
+impl<'a> AskedQuestion<'a> {
+    fn new<R: Rng>(index: usize, question: &'a Question, rng: &mut R) -> Self {
+        let mut answer_order: Vec<usize> = (0..question.answers.len()).collect();
+
+        if !question.config.ordered_answers {
+            rng::shuffle(&mut answer_order, rng);
+        }
+
+        Self { index, question, answer_order, inverse: None, repeat: false }
+    }
+
+    /// The inverse of a question: its correct answers are asked and its title is the answer.
+    ///
+    /// Returns `None` if the question has no correct answers that can be asked (only patterns).
+    fn inverse(index: usize, question: &'a Question) -> Option<Self> {
+        let answers: Vec<&str> = question.answers.iter()
+            .filter(|ans| ans.is_correct() && !matches!(ans, Answer::Regex(_)))
+            .map(Answer::text)
+            .collect();
+
+        if answers.is_empty() {
+            return None;
+        }
+
+        Some(Self { index, question, answer_order: vec![], inverse: Some(answers.join(", ")), repeat: false })
+    }
+
+    /// Index of the question in `Quiz::questions`.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn question(&self) -> &'a Question {
+        self.question
+    }
+
+    /// What's asked: the question's title, or its answers if this is the question's inverse.
+    pub fn title(&self) -> &str {
+        self.inverse.as_deref().unwrap_or(&self.question.title)
+    }
+
+    /// Whether this is the inverse of a question asked earlier (see the `ask_inverse` option).
+    pub fn is_inverse(&self) -> bool {
+        self.inverse.is_some()
+    }
+
+    /// Whether the question is being asked again since it was missed earlier (see
+    /// `QuizSession::practice`).
+    pub fn is_repeat(&self) -> bool {
+        self.repeat
+    }
+
+    pub fn config(&self) -> &'a Config {
+        &self.question.config
+    }
+
+    pub fn kind(&self) -> QuestionKind {
+        let num_correct_answers = self.question.answers
+            .iter()
+            .filter(|t| t.is_correct())
+            .count();
+
+        let typed_only = self.question.answers.iter().any(Answer::is_typed_only);
+
+        if self.is_inverse() || self.question.answers.len() == 1 || self.question.config.typed || typed_only {
+            QuestionKind::Typed
+        } else if num_correct_answers == 1 {
+            QuestionKind::SingleChoice
+        } else {
+            QuestionKind::MultipleChoice
+        }
+    }
+
+    /// The options to present to the quiz-taker, in the order they should be presented.
+    ///
+    /// Typed-answer questions have no options.
+    pub fn options(&self) -> Vec<&'a str> {
+        if self.kind() == QuestionKind::Typed {
+            return vec![];
+        }
+
+        self.answers().map(Answer::text).collect()
+    }
+
+    /// Parse raw user input into a `Response` fit for this question.
+    ///
+    /// Options are separated by spaces, semicolons, periods, or commas; anything that isn't an
+    /// option number is ignored.
+    pub fn parse_response(&self, input: &str) -> Response {
+        match self.kind() {
+            QuestionKind::Typed => Response::Text(input.trim().to_owned()),
+            _ => Response::Choices(input
+                .split(['.', ' ', ';', ','])
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .filter_map(|s| s.parse::<usize>().ok())
+                .collect()
+            ),
+        }
+    }
+
+    fn answers(&self) -> impl Iterator<Item = &'a Answer> + '_ {
+        self.answer_order.iter().map(|&i| &self.question.answers[i])
+    }
+
+    /// The correct response, with choices indexed as presented.
+    fn correct_response(&self) -> Response {
+        if self.is_inverse() {
+            return Response::Text(self.question.title.clone());
+        }
+
+        if self.kind() == QuestionKind::Typed {
+            // show the first accepted answer (patterns are a last resort, they don't read well)
+            let answers = || self.question.answers.iter().filter(|ans| ans.is_correct());
+
+            return match answers().find(|ans| !matches!(ans, Answer::Regex(_))).or_else(|| answers().next()) {
+                Some(Answer::Regex(regex)) => Response::Text(regex.to_string()),
+                Some(ans) => Response::Text(ans.text().to_owned()),
+                None => unreachable!(), // this question would fail to parse with `NoCorrectAnswer`
+            };
+        }
+
+        Response::Choices(self.answers()
+            .enumerate()
+            .filter(|(_, answer)| answer.is_correct())
+            .map(|(i, _)| i)
+            .collect()
+        )
+    }
+
+    fn grade(&self, response: &Response, hints_used: usize) -> Feedback {
+        let correct_response = self.correct_response();
+        let skipped = response.is_empty();
+        let mut matched_answer = None;
+        let mut accepted_with_typo = false;
+        let selected_answers = match response {
+            Response::Text(_) => vec![],
+            Response::Choices(choices) => {
+                let mut selected: Vec<usize> = choices.iter()
+                    .filter_map(|&i| self.answer_order.get(i).copied())
+                    .collect();
+                selected.sort();
+                selected.dedup();
+                selected
+            },
+        };
+
+        // the portion of the question's value earned
+        let credit = match (response, &correct_response) {
+            // skipped questions earn nothing (but aren't penalized)
+            _ if skipped => 0.0,
+            (Response::Text(user_answer), Response::Text(title)) if self.is_inverse() => {
+                let distance = fuzzy::edit_distance(&self.prepare(title), &self.prepare(user_answer));
+                accepted_with_typo = distance > 0 && distance <= self.question.config.fuzzy;
+
+                if distance <= self.question.config.fuzzy { 1.0 } else { 0.0 }
+            },
+            (Response::Text(user_answer), Response::Text(_)) => {
+                (matched_answer, accepted_with_typo) = match self.match_typed(user_answer) {
+                    Some((i, typo)) => (Some(i), typo),
+                    None => (None, false),
+                };
+
+                match matched_answer.map(|i| &self.question.answers[i]) {
+                    Some(ans) if ans.is_correct() => 1.0,
+                    _ => 0.0,
+                }
+            },
+            (Response::Choices(user_answers), Response::Choices(correct_answer_indicies)) => {
+                let mut user_answers = user_answers.clone();
+                user_answers.sort();
+                user_answers.dedup();
+
+                if self.kind() == QuestionKind::MultipleChoice {
+                    Self::partial_credit(
+                        self.question.config.scoring,
+                        &user_answers,
+                        correct_answer_indicies,
+                        self.question.answers.len(),
+                    )
+                } else if &user_answers == correct_answer_indicies {
+                    1.0
+                } else {
+                    0.0
+                }
+            },
+            _ => 0.0,
+        };
+
+        let value = self.value(hints_used);
+
+        // only penalize answers that earned nothing at all, partial credit is its own penalty
+        let points = if credit == 0.0 && !skipped {
+            -self.question.config.penalty
+        } else {
+            credit * value
+        };
+
+        Feedback {
+            is_correct: credit == 1.0,
+            skipped,
+            matched_answer,
+            accepted_with_typo,
+            selected_answers: match matched_answer {
+                Some(i) => vec![i],
+                None => selected_answers,
+            },
+            hints_used,
+            timed_out: false,
+            time_spent: Duration::ZERO,
+            points,
+            correct_response,
+            will_repeat: false,
+        }
+    }
+
+    /// What the question is worth after `hints_used` hints.
+    fn value(&self, hints_used: usize) -> f32 {
+        // each hint costs a fraction of the question's value (but never makes it negative)
+        self.question.config.value * (1.0 - hints_used as f32 * self.question.config.hint_penalty).max(0.0)
+    }
+
+    /// A readable version of `response`: the typed text, or the text of the chosen options.
+    fn describe_response(&self, response: &Response) -> String {
+        match response {
+            Response::Text(text) => text.trim().to_owned(),
+            Response::Choices(choices) => choices.iter()
+                .filter_map(|&i| self.answer_order.get(i))
+                .map(|&i| self.question.answers[i].text())
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+
+    /// Make typed text comparable according to the question's config.
+    fn prepare(&self, text: &str) -> String {
+        let config = &self.question.config;
+
+        let text = if config.case_sensitive { text.to_owned() } else { text.to_lowercase() };
+        if config.normalize { fuzzy::normalize(&text) } else { text.trim().to_owned() }
+    }
+
+    /// Find the answer (correct answers first) that a typed response matches, and whether it
+    /// only matched as a typo.
+    fn match_typed(&self, user_answer: &str) -> Option<(usize, bool)> {
+        let config = &self.question.config;
+        let prepare = |text: &str| self.prepare(text);
+
+        let user_answer = user_answer.trim();
+        let prepared_answer = prepare(user_answer);
+
+        let answers = || self.question.answers.iter().enumerate();
+
+        let exact = answers()
+            .find(|(_, ans)| match ans {
+                Answer::Correct(text) => prepare(text) == prepared_answer,
+                Answer::Numeric(numeric) => numeric.matches(user_answer, config.numeric_tolerance),
+                Answer::Regex(regex) => regex.is_match(user_answer, config.case_sensitive),
+                Answer::Incorrect(_) => false,
+            })
+            .or_else(|| answers().find(|(_, ans)| matches!(ans, Answer::Incorrect(text) if prepare(text) == prepared_answer)))
+            .map(|(i, _)| (i, false));
+
+        if exact.is_some() || config.fuzzy == 0 {
+            return exact;
+        }
+
+        // accept the closest correct answer that's within `fuzzy` typos
+        answers()
+            .filter_map(|(i, ans)| match ans {
+                Answer::Correct(text) => Some((i, fuzzy::edit_distance(&prepare(text), &prepared_answer))),
+                _ => None,
+            })
+            .filter(|&(_, distance)| distance <= config.fuzzy)
+            .min_by_key(|&(_, distance)| distance)
+            .map(|(i, _)| (i, true))
+    }
+
+    /// The portion (`0.0..=1.0`) of a multiple-answer question's value earned by selecting
+    /// `user_answers` out of `num_answers` options.
+    ///
+    /// Both `user_answers` and `correct_answer_indicies` must be sorted and free of duplicates.
+    fn partial_credit(scoring: Scoring, user_answers: &[usize], correct_answer_indicies: &[usize], num_answers: usize) -> f32 {
+        // out-of-range selections still count against the quiz-taker
+        let right = user_answers.iter().filter(|i| correct_answer_indicies.contains(i)).count();
+        let wrong = user_answers.len() - right;
+
+        match scoring {
+            Scoring::AllOrNothing => if user_answers == correct_answer_indicies { 1.0 } else { 0.0 },
+            // leaving out every wrong option isn't worth anything without picking a right one
+            Scoring::Proportional if right == 0 => 0.0,
+            Scoring::Proportional => {
+                // each option is worth a part of the question, earned by treating it correctly
+                let missed = correct_answer_indicies.len() - right;
+                let wrong_options = cmp::min(wrong, num_answers - correct_answer_indicies.len());
+
+                (num_answers - missed - wrong_options) as f32 / num_answers as f32
+            },
+            Scoring::RightMinusWrong => {
+                let net = right as f32 - wrong as f32;
+                (net / correct_answer_indicies.len() as f32).max(0.0)
+            },
+        }
+    }
+}
+
+/// A single attempt at a `Quiz`.
+///
+/// The questions (and the order of their answers) are decided when the session is created. First
+/// they're drawn, if the quiz or its pools or sections only ask a `sample` of their questions.
+/// Then questions with `ordered: false` are shuffled into a block at the start of their section
+/// (or of the quiz, if it has no sections), followed by the `ordered` questions in the order they
+/// were written.
+///
+/// All of the shuffling is driven by a single `Rng`, so a session created with the same seed
+/// (see `QuizSession::with_seed`) asks the questions in exactly the same order.
+///
+/// When practicing (see `QuizSession::practice` and the `repeat_missed` option), missed
+/// questions are asked again at random points later in the quiz, until they've been answered
+/// correctly enough times in a row. Only the first time a question is asked counts towards the
+/// score.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuizSession<'a> {
+    quiz: &'a Quiz,
+    questions: Vec<AskedQuestion<'a>>,
+    /// Index (into `questions`) of the question being asked
+    current: usize,
+    score: f32,
+    /// The seed `rng` was created with, if known
+    seed: Option<u64>,
+    /// How many hints have been given for the current question
+    hints_used: usize,
+    /// The maximum score of the questions being asked
+    total_score: f32,
+    /// The last question answered (index into `questions`), with its response and feedback
+    last_answered: Option<(usize, Response, Feedback)>,
+    /// Justifications and self-marked answers for a person to look over
+    review_log: Vec<ReviewEntry>,
+    /// Feedback for every question answered (or left unanswered) so far, in the order asked
+    feedback: Vec<Feedback>,
+    /// What the quiz-taker did on every question answered so far, for saving the attempt
+    answers: Vec<SavedAnswer>,
+    clock: SharedClock,
+    /// When (on `clock`) the quiz started
+    quiz_started: Duration,
+    /// Time spent on the quiz before it started (in an earlier sitting, for resumed attempts)
+    earlier_time: Duration,
+    /// When (on `clock`) the current question was first shown
+    question_started: Duration,
+    /// Whether the quiz's time limit ran out
+    timed_out: bool,
+    /// Picks where repeated questions are asked
+    rng: DefaultRng,
+    /// Whether every missed question is repeated, not just the ones with `repeat_missed`
+    practice: bool,
+    /// How the questions that were missed are coming along, in the order they were first missed
+    mastery: Vec<Mastery>,
+}
+
+impl<'a> QuizSession<'a> {
+    /// Start a session seeded with the quiz's `seed` option, or a random seed if it has none.
+    pub fn new(quiz: &'a Quiz) -> Self {
+        Self::with_seed(quiz, quiz.config.seed.unwrap_or_else(rng::random_seed))
+    }
+
+    /// Start a session that shuffles with the default `Rng` seeded with `seed`.
+    pub fn with_seed(quiz: &'a Quiz, seed: u64) -> Self {
+        let mut session = Self::with_rng(quiz, DefaultRng::seed_from_u64(seed));
+        session.seed = Some(seed);
+        session
+    }
+
+    /// Start a session that shuffles with `rng`.
+    pub fn with_rng<R: Rng>(quiz: &'a Quiz, mut rng: R) -> Self {
+        let mut questions = Vec::new();
+        questions.reserve_exact(quiz.questions.len());
+
+        // questions are only shuffled within their section
+        let drawn = Self::draw(quiz, &mut rng);
+        for section in drawn.chunk_by(|&a, &b| quiz.section_of(a) == quiz.section_of(b)) {
+            let mut shuffled = vec![];
+            let mut ordered_questions = vec![];
+
+            // set the order that questions will be asked in
+            for &i in section {
+                let question = &quiz.questions[i];
+                if question.config.ordered {
+                    ordered_questions.push((i, question));
+                } else {
+                    shuffled.push((i, question));
+                }
+            }
+
+            // randomly shuffle questions that desire to be randomly shuffled
+            rng::shuffle(&mut shuffled, &mut rng);
+            questions.append(&mut shuffled);
+
+            // append questions that desire to be presented in order (multi-part questions, etc)
+            questions.append(&mut ordered_questions);
+        }
+
+        let mut questions: Vec<AskedQuestion> = questions.into_iter()
+            .map(|(i, q)| AskedQuestion::new(i, q, &mut rng))
+            .collect();
+
+        // the inverses of questions are asked once everything else has been
+        let inverses: Vec<AskedQuestion> = questions.iter()
+            .filter(|asked| asked.question.config.ask_inverse)
+            .filter_map(|asked| AskedQuestion::inverse(asked.index, asked.question))
+            .collect();
+        questions.extend(inverses);
+
+        let rng = DefaultRng::seed_from_u64(rng.next_u64());
+        Self::with_questions(quiz, questions, rng)
+    }
+
+    /// Pick the questions to ask (indices into `Quiz::questions`, in the order they were written).
+    ///
+    /// Pools with a `sample` size (set on any of their questions) are narrowed down to that many
+    /// random questions first, then sections with a `sample` size. Those draws are always asked;
+    /// the quiz's own `sample` size is filled up with the rest of the questions.
+    fn draw<R: Rng>(quiz: &Quiz, rng: &mut R) -> Vec<usize> {
+        let mut drawn: Vec<usize> = (0..quiz.questions.len()).collect();
+        // questions drawn from sampled pools and sections
+        let mut kept: Vec<usize> = vec![];
+        let pool = |i: usize| quiz.questions[i].config.pool.as_deref();
+
+        // each pool's size is the first one set on its questions
+        let mut pools: Vec<(&str, Option<usize>)> = vec![];
+        for question in &quiz.questions {
+            let Some(name) = question.config.pool.as_deref() else { continue };
+
+            match pools.iter_mut().find(|(pool, _)| *pool == name) {
+                Some((_, sample)) => *sample = sample.or(question.config.sample),
+                None => pools.push((name, question.config.sample)),
+            }
+        }
+
+        for (name, sample) in pools {
+            let Some(sample) = sample else { continue };
+
+            let mut in_pool: Vec<usize> = drawn.iter().copied().filter(|&i| pool(i) == Some(name)).collect();
+            rng::sample(&mut in_pool, sample, rng);
+            drawn.retain(|i| pool(*i) != Some(name) || in_pool.contains(i));
+            kept.extend(in_pool);
+        }
+
+        for section in &quiz.sections {
+            let Some(sample) = section.config().sample else { continue };
+
+            let mut in_section: Vec<usize> = drawn.iter().copied().filter(|i| section.questions().contains(i)).collect();
+            rng::sample(&mut in_section, sample, rng);
+            drawn.retain(|i| !section.questions().contains(i) || in_section.contains(i));
+            kept.extend(in_section);
+        }
+
+        if let Some(sample) = quiz.config.sample {
+            let mut rest: Vec<usize> = drawn.iter().copied().filter(|i| !kept.contains(i)).collect();
+            rng::sample(&mut rest, sample.saturating_sub(kept.len()), rng);
+            drawn.retain(|i| kept.contains(i) || rest.contains(i));
+        }
+
+        drawn
+    }
+
+    /// Start a session that asks `questions` (in order).
+    fn with_questions(quiz: &'a Quiz, questions: Vec<AskedQuestion<'a>>, rng: DefaultRng) -> Self {
+        let total_score = Self::max_score(&questions);
+
+        Self {
+            quiz,
+            questions,
+            current: 0,
+            score: 0.0,
+            seed: None,
+            hints_used: 0,
+            total_score,
+            last_answered: None,
+            review_log: vec![],
+            feedback: vec![],
+            answers: vec![],
+            clock: SharedClock::default(),
+            quiz_started: Duration::ZERO,
+            earlier_time: Duration::ZERO,
+            question_started: Duration::ZERO,
+            timed_out: false,
+            rng,
+            practice: false,
+            mastery: vec![],
+        }
+    }
+
+    /// The maximum score of `questions` (repeats don't count).
+    fn max_score(questions: &[AskedQuestion]) -> f32 {
+        questions.iter()
+            .filter(|asked| !asked.repeat)
+            .map(|asked| asked.question.config.value)
+            .sum()
+    }
+
+    /// Repeat every missed question later in the quiz until it's mastered, as if every question
+    /// had the `repeat_missed` option.
+    pub fn practice(mut self) -> Self {
+        self.practice = true;
+        self
+    }
+
+    /// Only ask the questions `keep` returns `true` for (given their index in `Quiz::questions`),
+    /// like the ones that are due for review (see `study`). Must be called before any questions
+    /// are answered.
+    pub fn only(mut self, mut keep: impl FnMut(usize, &Question) -> bool) -> Self {
+        assert_eq!(self.current, 0, "questions can't be dropped once the quiz has started");
+
+        self.questions.retain(|asked| keep(asked.index, asked.question));
+        self.total_score = Self::max_score(&self.questions);
+        self
+    }
+
+    /// Time the session with `clock` instead of the system clock (the timers are restarted).
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = SharedClock::new(clock);
+        self.start();
+        self
+    }
+
+    /// (Re)start the quiz's and the current question's timers, for when the first question is
+    /// actually shown.
+    ///
+    /// Sessions start timing when they're created, so this only matters for time limits. Time
+    /// already spent on answered questions (in a resumed attempt) still counts towards the quiz's
+    /// time limit.
+    pub fn start(&mut self) {
+        self.earlier_time = self.feedback.iter().map(|feedback| feedback.time_spent).sum();
+        self.quiz_started = self.clock.now();
+        self.question_started = self.quiz_started;
+    }
+
+    /// Save the state of the attempt, so it can be resumed with `QuizSession::resume`.
+    pub fn save(&self) -> SavedAttempt {
+        SavedAttempt {
+            quiz: self.quiz.fingerprint(),
+            seed: self.seed,
+            timed_out: self.timed_out,
+            practice: self.practice,
+            hints_used: self.hints_used,
+            questions: self.questions.iter()
+                .map(|asked| SavedQuestion {
+                    index: asked.index,
+                    inverse: asked.is_inverse(),
+                    answer_order: asked.answer_order.clone(),
+                    repeat: asked.repeat,
+                })
+                .collect(),
+            answers: self.answers.clone(),
+        }
+    }
+
+    /// The results of the attempt so far (only the first time each question was asked).
+    pub fn result(&self) -> QuizResult {
+        let questions = self.questions.iter()
+            .zip(self.feedback.iter().zip(&self.answers))
+            .filter(|(asked, _)| !asked.repeat)
+            .map(|(asked, (feedback, answer))| QuestionResult {
+                index: asked.index,
+                title: asked.title().to_owned(),
+                inverse: asked.is_inverse(),
+                answer: asked.describe_response(&answer.response),
+                correct_answers: match &asked.inverse {
+                    Some(_) => vec![asked.question.title.clone()],
+                    None => asked.question.answers.iter()
+                        .filter(|ans| ans.is_correct())
+                        .map(|ans| ans.text().to_owned())
+                        .collect(),
+                },
+                points: feedback.points,
+                value: asked.question.config.value,
+                is_correct: feedback.is_correct,
+                skipped: feedback.skipped,
+                timed_out: feedback.timed_out,
+                time_spent: feedback.time_spent,
+                hints_used: feedback.hints_used,
+            })
+            .collect();
+
+        QuizResult {
+            metadata: self.quiz.metadata(),
+            seed: self.seed,
+            score: self.score,
+            total_score: self.total_score,
+            timed_out: self.timed_out,
+            questions,
+        }
+    }
+
+    /// The first try at every question answered so far, with its feedback (repeats are left out,
+    /// like in `QuizSession::result`).
+    pub(crate) fn first_tries(&self) -> impl Iterator<Item = (&AskedQuestion<'a>, &Feedback)> {
+        self.questions.iter()
+            .zip(&self.feedback)
+            .filter(|(asked, _)| !asked.repeat)
+    }
+
+    /// Continue a saved attempt at `quiz`, with the same questions in the same order, and the
+    /// answers given so far.
+    ///
+    /// Fails if the quiz has changed since the attempt was saved.
+    pub fn resume(quiz: &'a Quiz, attempt: &SavedAttempt) -> Result<Self, ResumeError> {
+        if attempt.quiz != quiz.fingerprint() {
+            return Err(ResumeError::QuizChanged);
+        }
+
+        let questions = attempt.questions.iter()
+            .map(|saved| {
+                let question = quiz.questions.get(saved.index)?;
+
+                if saved.inverse {
+                    return AskedQuestion::inverse(saved.index, question)
+                        .map(|asked| AskedQuestion { repeat: saved.repeat, ..asked });
+                }
+
+                let mut order = saved.answer_order.clone();
+                order.sort_unstable();
+                if !order.iter().copied().eq(0..question.answers.len()) {
+                    return None;
+                }
+
+                Some(AskedQuestion { index: saved.index, question, answer_order: saved.answer_order.clone(), inverse: None, repeat: saved.repeat })
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ResumeError::Mismatch)?;
+
+        if attempt.answers.len() > questions.len() {
+            return Err(ResumeError::Mismatch);
+        }
+
+        // repeats are placed where they were, so the rest of the attempt doesn't need the same rng
+        let mut session = Self::with_questions(quiz, questions, DefaultRng::seed_from_u64(rng::random_seed()));
+        session.seed = attempt.seed;
+        session.practice = attempt.practice;
+
+        // replay the attempt
+        for answer in &attempt.answers {
+            session.hints_used = answer.hints_used;
+            session.record(answer.response.clone(), answer.time_spent, answer.timed_out);
+
+            if let Some(justification) = &answer.justification {
+                session.justify(justification);
+            }
+            if answer.marked_correct {
+                session.mark_correct();
+            }
+        }
+
+        session.timed_out = attempt.timed_out;
+        // hints already given for the current question still count against it
+        if !session.is_finished() {
+            session.hints_used = attempt.hints_used;
+        }
+        session.start();
+        Ok(session)
+    }
+
+    pub fn quiz(&self) -> &'a Quiz {
+        self.quiz
+    }
+
+    /// The seed of the session's `Rng`, if it was created from one.
+    ///
+    /// Passing it to `QuizSession::with_seed` replays the session's question and answer order.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// The question waiting for a response, or `None` if the quiz is finished.
+    pub fn current_question(&self) -> Option<&AskedQuestion<'a>> {
+        self.questions.get(self.current)
+    }
+
+    /// Grade `response` against the current question and move on to the next one.
+    ///
+    /// If the question's (or the quiz's) time ran out before the response was submitted, the
+    /// response doesn't count and the question is graded as skipped. If the quiz's time ran out,
+    /// the remaining questions are left unanswered and the quiz is finished.
+    ///
+    /// A missed question that's being practiced is asked again at a random point later on.
+    ///
+    /// Returns `None` if the quiz is already finished.
+    pub fn submit(&mut self, response: Response) -> Option<Feedback> {
+        if self.is_finished() {
+            return None;
+        }
+
+        let now = self.clock.now();
+        let deadline = self.deadline().filter(|&deadline| now >= deadline);
+        let response = match deadline {
+            Some(_) => Response::Text(String::new()), // too late
+            None => response,
+        };
+
+        // there's no point in repeating questions once the quiz is over
+        if self.quiz_deadline().is_some_and(|deadline| now >= deadline) {
+            self.timed_out = true;
+        }
+
+        let time_spent = deadline.unwrap_or(now).saturating_sub(self.question_started);
+        let feedback = self.record(response, time_spent, deadline.is_some());
+        self.question_started = now;
+
+        if self.timed_out {
+            self.time_out();
+        } else if feedback.will_repeat {
+            self.repeat_last();
+        }
+
+        Some(feedback)
+    }
+
+    /// Submit the current question without an answer if its (or the quiz's) time has run out, for
+    /// frontends that can check the time while waiting for an answer.
+    ///
+    /// Returns the question's feedback if it was submitted.
+    pub fn check_time(&mut self) -> Option<Feedback> {
+        if self.time_remaining()? > Duration::ZERO {
+            return None;
+        }
+
+        self.submit(Response::Text(String::new()))
+    }
+
+    /// How long the quiz-taker has left to answer the current question (the sooner of its time
+    /// limit and the quiz's), or `None` if there's no time limit.
+    pub fn time_remaining(&self) -> Option<Duration> {
+        Some(self.deadline()?.saturating_sub(self.clock.now()))
+    }
+
+    /// Whether the quiz's time limit ran out (leaving the remaining questions unanswered).
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    /// Feedback for every question answered (or left unanswered) so far, in the order asked.
+    pub fn feedback(&self) -> &[Feedback] {
+        &self.feedback
+    }
+
+    /// The points earned in each of the quiz's sections (see `Quiz::sections`) with questions being
+    /// asked, in the order they were written. Repeats don't count, like in the session's score.
+    pub fn section_scores(&self) -> Vec<SectionScore> {
+        let mut scores: Vec<SectionScore> = vec![];
+
+        for (i, asked) in self.questions.iter().enumerate().filter(|(_, asked)| !asked.repeat) {
+            let Some(section) = self.quiz.section_of(asked.index) else { continue };
+            let points = self.feedback.get(i).map_or(0.0, |feedback| feedback.points);
+
+            match scores.iter_mut().find(|score| score.section == section) {
+                Some(score) => {
+                    score.score += points;
+                    score.total_score += asked.question.config.value;
+                },
+                None => scores.push(SectionScore { section, score: points, total_score: asked.question.config.value }),
+            }
+        }
+
+        scores.sort_by_key(|score| score.section);
+        scores
+    }
+
+    /// How the questions that were missed while practicing are coming along (see
+    /// `QuizSession::practice`).
+    pub fn mastery(&self) -> &[Mastery] {
+        &self.mastery
+    }
+
+    /// When (on `clock`) the quiz's time limit runs out.
+    fn quiz_deadline(&self) -> Option<Duration> {
+        Some(self.quiz_started + self.quiz.config.time_limit?.saturating_sub(self.earlier_time))
+    }
+
+    /// When (on `clock`) the current question has to be answered by.
+    fn deadline(&self) -> Option<Duration> {
+        let question_deadline = self.current_question()?.config().time_limit.map(|limit| self.question_started + limit);
+
+        match (self.quiz_deadline(), question_deadline) {
+            (Some(quiz), Some(question)) => Some(cmp::min(quiz, question)),
+            (quiz, question) => quiz.or(question),
+        }
+    }
+
+    /// Grade `response` against the current question and move on to the next one.
+    fn record(&mut self, response: Response, time_spent: Duration, timed_out: bool) -> Feedback {
+        let asked = &self.questions[self.current];
+        let mut feedback = asked.grade(&response, self.hints_used);
+        feedback.timed_out = timed_out;
+        feedback.time_spent = time_spent;
+
+        // only the first try counts
+        if asked.repeat {
+            feedback.points = 0.0;
+        }
+
+        // skipped (and timed out) questions aren't repeated, or running out of answers would never end
+        if (self.practice || asked.config().repeat_missed) && !feedback.skipped {
+            feedback.will_repeat = self.practiced(feedback.is_correct) && !self.timed_out;
+        }
+
+        self.answers.push(SavedAnswer {
+            response: response.clone(),
+            hints_used: self.hints_used,
+            time_spent,
+            timed_out,
+            marked_correct: false,
+            justification: None,
+        });
+
+        self.score += feedback.points;
+        self.last_answered = Some((self.current, response, feedback.clone()));
+        self.feedback.push(feedback.clone());
+        self.current += 1;
+        self.hints_used = 0;
+
+        feedback
+    }
+
+    /// Keep track of how the current question is coming along after it was answered (correctly
+    /// or not).
+    ///
+    /// Returns whether it still needs practice.
+    fn practiced(&mut self, is_correct: bool) -> bool {
+        let asked = &self.questions[self.current];
+        let found = self.mastery.iter_mut().find(|mastery| mastery.question == asked.index && mastery.inverse == asked.is_inverse());
+
+        let mastery = match found {
+            Some(mastery) => mastery,
+            // questions that are answered correctly the first time don't need practice
+            None if is_correct => return false,
+            None => {
+                self.mastery.push(Mastery {
+                    question: asked.index,
+                    title: asked.title().to_owned(),
+                    inverse: asked.is_inverse(),
+                    attempts: 0,
+                    misses: 0,
+                    streak: 0,
+                    required: asked.config().mastery.max(1),
+                });
+                self.mastery.last_mut().expect("mastery was just added")
+            },
+        };
+
+        mastery.attempts += 1;
+        if is_correct {
+            mastery.streak += 1;
+        } else {
+            mastery.misses += 1;
+            mastery.streak = 0;
+        }
+
+        !mastery.is_mastered()
+    }
+
+    /// Ask the last question answered again, at a random point later in the quiz (but not right
+    /// away, unless it's the last question left).
+    fn repeat_last(&mut self) {
+        let mut asked = self.questions[self.current - 1].clone();
+        asked.repeat = true;
+
+        let remaining = self.questions.len() - self.current;
+        let at = match remaining {
+            0 => self.current,
+            _ => self.current + 1 + self.rng.below(remaining),
+        };
+
+        self.questions.insert(at, asked);
+    }
+
+    /// End the quiz, leaving every remaining question unanswered.
+    fn time_out(&mut self) {
+        while !self.is_finished() {
+            self.record(Response::Text(String::new()), Duration::ZERO, true);
+        }
+
+        self.timed_out = true;
+    }
+
+    /// Record why the quiz-taker gave their last answer, if its question asks for a
+    /// justification (see the `justified` option).
+    ///
+    /// Returns whether the justification was recorded.
+    pub fn justify(&mut self, justification: &str) -> bool {
+        let Some((i, response, feedback)) = &self.last_answered else { return false };
+        let asked = &self.questions[*i];
+
+        if !asked.question.config.justified || feedback.skipped || justification.trim().is_empty() {
+            return false;
+        }
+
+        self.answers[*i].justification = Some(justification.trim().to_owned());
+
+        self.review_log.push(ReviewEntry {
+            question: asked.index,
+            title: asked.title().to_owned(),
+            answer: asked.describe_response(response),
+            kind: ReviewKind::Justification(justification.trim().to_owned()),
+        });
+
+        true
+    }
+
+    /// Let the quiz-taker mark their last answer as correct after it was graded as incorrect, if
+    /// its question allows it (see the `allow_review` option). They're given the points they
+    /// would have gotten for a correct answer, and the answer is logged for review.
+    ///
+    /// Returns the new feedback for the answer, or `None` if it can't be marked correct (it was
+    /// already correct, it was skipped, it was a repeat, or the question doesn't allow it).
+    pub fn mark_correct(&mut self) -> Option<Feedback> {
+        let (i, response, feedback) = self.last_answered.as_mut()?;
+        let asked = &self.questions[*i];
+
+        if !asked.question.config.allow_review || feedback.is_correct || feedback.skipped || asked.repeat {
+            return None;
+        }
+
+        let points = asked.value(feedback.hints_used);
+        self.score += points - feedback.points;
+        feedback.points = points;
+        feedback.is_correct = true;
+
+        // it doesn't need practicing after all (it wasn't a repeat, so this was its first miss)
+        let requeued = feedback.will_repeat;
+        feedback.will_repeat = false;
+
+        self.feedback[*i] = feedback.clone();
+        self.answers[*i].marked_correct = true;
+
+        self.review_log.push(ReviewEntry {
+            question: asked.index,
+            title: asked.title().to_owned(),
+            answer: asked.describe_response(response),
+            kind: ReviewKind::MarkedCorrect { correct_answer: asked.describe_response(&feedback.correct_response) },
+        });
+
+        let (index, inverse) = (asked.index, asked.is_inverse());
+        let feedback = feedback.clone();
+
+        if requeued {
+            if let Some(at) = (self.current..self.questions.len())
+                .find(|&at| self.questions[at].repeat && self.questions[at].index == index && self.questions[at].is_inverse() == inverse)
+            {
+                self.questions.remove(at);
+            }
+            self.mastery.retain(|mastery| mastery.question != index || mastery.inverse != inverse);
+        }
+
+        Some(feedback)
+    }
+
+    /// Everything the quiz-taker has justified or marked correct themselves, for a person to look
+    /// over.
+    pub fn review_log(&self) -> &[ReviewEntry] {
+        &self.review_log
+    }
+
+    /// Give the next hint for the current question (each one lowers the points it's worth by the
+    /// question's `hint_penalty`).
+    ///
+    /// Returns `None` if there are no hints left (or the quiz is finished).
+    pub fn hint(&mut self) -> Option<&'a str> {
+        let hint = self.current_question()?.question.hints.get(self.hints_used)?;
+        self.hints_used += 1;
+
+        Some(hint)
+    }
+
+    /// How many hints have been given for the current question.
+    pub fn hints_used(&self) -> usize {
+        self.hints_used
+    }
+
+    /// The points earned so far.
+    pub fn score(&self) -> f32 {
+        self.score
+    }
+
+    /// The maximum score of the questions being asked (including the inverses of questions, but
+    /// not repeats).
+    pub fn total_score(&self) -> f32 {
+        self.total_score
+    }
+
+    /// How many questions have been answered and how many there are in total (including the
+    /// repeats so far).
+    pub fn progress(&self) -> (usize, usize) {
+        (self.current, self.questions.len())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current >= self.questions.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attempt::SavedAttempt;
+    use crate::clock::ManualClock;
+
+    #[test]
+    fn session_order() {
+        let quiz: Quiz = "?question\n+answer\n\n?question2\n+answer\n-incorrect".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+
+        let question = session.current_question().expect("session should have a question");
+        assert_eq!(question.title(), "question");
+        assert_eq!(question.kind(), QuestionKind::Typed);
+        assert!(question.options().is_empty());
+
+        session.submit(Response::Text("answer".to_owned()));
+
+        let question = session.current_question().expect("session should have a second question");
+        assert_eq!(question.title(), "question2");
+        assert_eq!(question.kind(), QuestionKind::SingleChoice);
+        assert_eq!(question.options(), vec!["answer", "incorrect"]);
+
+        session.submit(Response::Choices(vec![1]));
+
+        assert!(session.is_finished());
+        assert_eq!(session.current_question(), None);
+        assert_eq!(session.submit(Response::Choices(vec![0])), None);
+        assert_eq!(session.score(), 1.0);
+        assert_eq!(session.progress(), (2, 2));
+    }
+
+    #[test]
+    fn session_feedback() {
+        let quiz: Quiz = ";value: 2\n?question\n-incorrect\n+answer\n+also correct".parse().expect("quiz should parse");
+        let clock = ManualClock::new();
+        let mut session = QuizSession::new(&quiz).with_clock(clock.clone());
+
+        let question = session.current_question().expect("session should have a question");
+        assert_eq!(question.kind(), QuestionKind::MultipleChoice);
+
+        let response = question.parse_response("2, 1");
+        assert_eq!(response, Response::Choices(vec![2, 1]));
+
+        clock.advance(Duration::from_secs(3));
+
+        let feedback = session.submit(response).expect("session should accept a response");
+        assert_eq!(feedback, Feedback {
+            is_correct: true,
+            skipped: false,
+            matched_answer: None,
+            accepted_with_typo: false,
+            selected_answers: vec![1, 2],
+            hints_used: 0,
+            timed_out: false,
+            time_spent: Duration::from_secs(3),
+            points: 2.0,
+            correct_response: Response::Choices(vec![1, 2]),
+            will_repeat: false,
+        });
+        assert_eq!(session.feedback(), [feedback]);
+    }
+
+    #[test]
+    fn session_question_time_limit() {
+        let quiz: Quiz = "?question\n+answer\n\n;time-limit: 10s\n?question2\n+answer\n\n?question3\n+answer".parse().expect("quiz should parse");
+        let clock = ManualClock::new();
+        let mut session = QuizSession::new(&quiz).with_clock(clock.clone());
+        assert_eq!(session.time_remaining(), None);
+
+        clock.advance(Duration::from_secs(60));
+        assert!(session.submit(Response::Text("answer".to_owned())).expect("session should accept a response").is_correct);
+
+        assert_eq!(session.time_remaining(), Some(Duration::from_secs(10)));
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(session.check_time(), None);
+        assert_eq!(session.time_remaining(), Some(Duration::from_secs(6)));
+
+        // answers that come too late don't count
+        clock.advance(Duration::from_secs(20));
+        let feedback = session.submit(Response::Text("answer".to_owned())).expect("session should accept a response");
+        assert!(feedback.timed_out && feedback.skipped && !feedback.is_correct);
+        assert_eq!(feedback.time_spent, Duration::from_secs(10));
+
+        // question time limits aren't inherited from the file
+        assert_eq!(session.time_remaining(), None);
+        assert!(!session.timed_out());
+        assert_eq!(session.score(), 1.0);
+    }
+
+    #[test]
+    fn session_quiz_time_limit() {
+        let quiz: Quiz = ";time-limit: 1m\n---\n?question\n+answer\n\n;time-limit: 45s\n?question2\n+answer\n\n?question3\n+answer\n\n?question4\n+answer".parse().expect("quiz should parse");
+        let clock = ManualClock::new();
+        let mut session = QuizSession::new(&quiz).with_clock(clock.clone());
+
+        clock.advance(Duration::from_secs(20));
+        assert!(session.submit(Response::Text("answer".to_owned())).expect("session should accept a response").is_correct);
+
+        // the quiz's time runs out before the question's
+        assert_eq!(session.time_remaining(), Some(Duration::from_secs(40)));
+        clock.advance(Duration::from_secs(40));
+        let feedback = session.check_time().expect("question should time out");
+        assert!(feedback.timed_out);
+        assert_eq!(feedback.time_spent, Duration::from_secs(40));
+
+        // the remaining questions are left unanswered
+        assert!(session.is_finished());
+        assert!(session.timed_out());
+        assert_eq!(session.progress(), (4, 4));
+        assert_eq!(session.score(), 1.0);
+
+        let feedback = session.feedback();
+        assert_eq!(feedback.len(), 4);
+        assert!(!feedback[0].timed_out);
+        assert!(feedback[1..].iter().all(|feedback| feedback.timed_out && feedback.skipped && feedback.points == 0.0));
+    }
+
+    #[test]
+    fn session_save_and_resume() {
+        let source = ";ordered: false\n;ordered-answers: false\n---\n;justified: true\n?question\n+answer\n-wrong\n-also wrong\n\n\
+            ;allow-review: true\n;ask-inverse: true\n?question2\n+answer\n\n?question3\n+answer\n~ hint\n\n?question4\n+answer";
+        let quiz: Quiz = source.parse().expect("quiz should parse");
+        let clock = ManualClock::new();
+        let mut session = QuizSession::with_seed(&quiz, 7).with_clock(clock.clone());
+
+        // answer a couple of questions however they were shuffled
+        for _ in 0..3 {
+            let asked = session.current_question().expect("session should have a question").clone();
+            session.hint();
+            clock.advance(Duration::from_secs(2));
+
+            let response = match asked.kind() {
+                QuestionKind::Typed => Response::Text("wrong answer".to_owned()),
+                _ => Response::Choices(vec![0]),
+            };
+            session.submit(response);
+            session.justify("just because");
+            session.mark_correct();
+        }
+
+        let saved = session.save().to_string();
+        let attempt: SavedAttempt = saved.parse().expect("saved attempt should parse");
+        let resumed = QuizSession::resume(&quiz, &attempt).expect("attempt should resume");
+
+        assert_eq!(resumed.progress(), session.progress());
+        assert_eq!(resumed.current_question(), session.current_question());
+        assert_eq!(resumed.score(), session.score());
+        assert_eq!(resumed.total_score(), session.total_score());
+        assert_eq!(resumed.feedback(), session.feedback());
+        assert_eq!(resumed.review_log(), session.review_log());
+        assert_eq!(resumed.seed(), Some(7));
+        assert_eq!(resumed.save(), session.save());
+
+        // attempts can't be resumed once the quiz changes
+        let changed: Quiz = source.replace("question4", "question 4").parse().expect("quiz should parse");
+        assert_eq!(QuizSession::resume(&changed, &attempt), Err(ResumeError::QuizChanged));
+
+        let mut mismatched = attempt.clone();
+        mismatched.questions[0].index = 9;
+        assert_eq!(QuizSession::resume(&quiz, &mismatched), Err(ResumeError::Mismatch));
+    }
+
+    #[test]
+    fn session_resume_keeps_time_spent() {
+        let quiz: Quiz = ";time-limit: 1m\n---\n?question\n+answer\n\n?question2\n+answer".parse().expect("quiz should parse");
+        let clock = ManualClock::new();
+        let mut session = QuizSession::new(&quiz).with_clock(clock.clone());
+
+        clock.advance(Duration::from_secs(45));
+        session.submit(Response::Text("answer".to_owned()));
+
+        let resumed = QuizSession::resume(&quiz, &session.save()).expect("attempt should resume").with_clock(ManualClock::new());
+        assert_eq!(resumed.time_remaining(), Some(Duration::from_secs(15)));
+    }
+
+    #[test]
+    fn session_resume_keeps_hints() {
+        let quiz: Quiz = ";hint-penalty: 0.5\n---\n?question\n+answer\n~ first\n~ second".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+        session.hint();
+
+        let attempt: SavedAttempt = session.save().to_string().parse().expect("saved attempt should parse");
+        let mut resumed = QuizSession::resume(&quiz, &attempt).expect("attempt should resume");
+        assert_eq!(resumed.hints_used(), 1);
+        assert_eq!(resumed.hint(), Some("second"));
+
+        let feedback = resumed.submit(Response::Text("answer".to_owned())).expect("session should accept a response");
+        assert_eq!(feedback.hints_used, 2);
+    }
+
+    #[test]
+    fn session_result() {
+        let quiz: Quiz = ";title: Rocks\n---\n;value: 2\n?question\n-incorrect\n+answer\n+also correct\n\n;ask-inverse: true\n?question2\n+answer\n~ hint\n\n?question3\n+answer"
+            .parse().expect("quiz should parse");
+        let clock = ManualClock::new();
+        let mut session = QuizSession::new(&quiz).with_clock(clock.clone());
+
+        session.submit(Response::Choices(vec![1]));
+        session.hint();
+        clock.advance(Duration::from_secs(5));
+        session.submit(Response::Text("answer".to_owned()));
+        session.submit(Response::Text(String::new()));
+        session.submit(Response::Text("question2".to_owned()));
+
+        let result = session.result();
+        assert_eq!(result.metadata.title.as_deref(), Some("Rocks"));
+        assert_eq!((result.score, result.total_score), (2.0, 5.0));
+        assert_eq!(result.questions.len(), 4);
+
+        let first = &result.questions[0];
+        assert_eq!((first.answer.as_str(), first.points, first.value), ("answer", 0.0, 2.0));
+        assert_eq!(first.correct_answers, ["answer", "also correct"]);
+
+        let second = &result.questions[1];
+        assert!(second.is_correct && second.used_hint());
+        assert_eq!(second.time_spent, Duration::from_secs(5));
+
+        assert!(result.questions[2].skipped);
+
+        let inverse = &result.questions[3];
+        assert!(inverse.inverse && inverse.is_correct);
+        assert_eq!((inverse.title.as_str(), inverse.correct_answers.as_slice()), ("answer", &["question2".to_owned()][..]));
+    }
+
+    #[test]
+    fn session_only() {
+        let quiz: Quiz = "?question\n+answer\n\n;value: 2\n;ask-inverse: true\n?question2\n+answer\n\n?question3\n+answer".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz).only(|i, _| i == 1);
+
+        assert_eq!(session.progress(), (0, 2));
+        assert_eq!(session.total_score(), 4.0);
+        assert_eq!(session.current_question().map(AskedQuestion::title), Some("question2"));
+        session.submit(Response::Text("answer".to_owned()));
+        assert!(session.current_question().is_some_and(AskedQuestion::is_inverse));
+    }
+
+    #[test]
+    fn session_practice() {
+        let quiz: Quiz = "?question\n+answer\n\n?question2\n+answer\n\n;mastery: 1\n?question3\n+answer".parse().expect("quiz should parse");
+        let mut session = QuizSession::with_seed(&quiz, 3).practice();
+
+        let first = session.submit(Response::Text("answer".to_owned())).expect("session should accept a response");
+        assert!(!first.will_repeat);
+
+        let missed = session.submit(Response::Text("wrong".to_owned())).expect("session should accept a response");
+        assert!(missed.will_repeat);
+        assert_eq!(session.progress(), (2, 4));
+
+        // the missed question comes back after question3, which it can't skip ahead of
+        assert_eq!(session.current_question().map(AskedQuestion::title), Some("question3"));
+        session.submit(Response::Text("wrong".to_owned()));
+        assert_eq!(session.progress(), (3, 5));
+
+        // until each is answered correctly enough times in a row
+        let mut asked = vec![];
+        while let Some(question) = session.current_question().cloned() {
+            assert!(question.is_repeat());
+            asked.push(question.title().to_owned());
+
+            // question3 is missed again the first time it comes back
+            let missed_again = asked == ["question3"] || asked == ["question2", "question3"];
+            session.submit(Response::Text(if missed_again { "wrong" } else { "answer" }.to_owned()));
+        }
+        assert_eq!(asked.iter().filter(|title| *title == "question2").count(), 2);
+        assert_eq!(asked.iter().filter(|title| *title == "question3").count(), 2);
+
+        assert_eq!(session.mastery().len(), 2);
+        assert!(session.mastery().iter().all(Mastery::is_mastered));
+        let mastery = |i: usize| session.mastery().iter().find(|mastery| mastery.question == i).expect("question was missed");
+        assert_eq!((mastery(1).attempts, mastery(1).misses, mastery(1).required), (3, 1, 2));
+        assert_eq!((mastery(2).attempts, mastery(2).misses, mastery(2).required), (3, 2, 1));
+
+        // repeats don't count towards the score
+        assert_eq!((session.score(), session.total_score()), (1.0, 3.0));
+        assert_eq!(session.result().questions.len(), 3);
+
+        // and they're asked in the same places when the attempt is resumed
+        let resumed = QuizSession::resume(&quiz, &session.save()).expect("attempt should resume");
+        assert_eq!(resumed.save(), session.save());
+        assert_eq!(resumed.mastery(), session.mastery());
+        assert_eq!(resumed.score(), session.score());
+
+        // questions are only repeated with `repeat-missed` (or when practicing)
+        let quiz: Quiz = "?question\n+answer\n\n;repeat-missed: true\n?question2\n+answer".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+        session.submit(Response::Text("wrong".to_owned()));
+        session.submit(Response::Text("wrong".to_owned()));
+        assert_eq!(session.progress(), (2, 3));
+        assert_eq!(session.current_question().map(AskedQuestion::title), Some("question2"));
+    }
+
+    #[test]
+    fn session_sample() {
+        let mut source = ";sample: 4\n---\n".to_owned();
+        for i in 0..10 {
+            source += &format!(";pool: Geology\n;sample: 3\n;value: 2\n?geology{i}\n+answer\n\n");
+        }
+        for i in 0..10 {
+            source += &format!(";pool: Chemistry\n?chemistry{i}\n+answer\n\n");
+        }
+        source += "?unpooled\n+answer";
+        let quiz: Quiz = source.parse().expect("quiz should parse");
+
+        for seed in 0..20 {
+            let session = QuizSession::with_seed(&quiz, seed);
+            let drawn: Vec<usize> = session.questions.iter().map(|asked| asked.index).collect();
+
+            assert_eq!(drawn.len(), 4);
+            assert!(drawn.is_sorted(), "ordered questions stay in order");
+            assert_eq!(drawn.iter().filter(|&&i| i < 10).count(), 3, "the geology pool's draw is kept");
+
+            // the total only counts the questions drawn
+            let total: f32 = drawn.iter().map(|&i| quiz.questions[i].config.value).sum();
+            assert_eq!(session.total_score(), total);
+        }
+
+        assert_eq!(asked_order(&QuizSession::with_seed(&quiz, 5)), asked_order(&QuizSession::with_seed(&quiz, 5)));
+
+        // pools are sampled even if the quiz isn't
+        let quiz: Quiz = source.replacen(";sample: 4", "", 1).parse().expect("quiz should parse");
+        let session = QuizSession::new(&quiz);
+        assert_eq!(session.progress(), (0, 14));
+        assert_eq!(session.total_score(), 17.0);
+
+        // a quiz sample smaller than its pools' draws still asks all of them
+        let quiz: Quiz = source.replacen(";sample: 4", ";sample: 2", 1).parse().expect("quiz should parse");
+        let drawn: Vec<usize> = QuizSession::new(&quiz).questions.iter().map(|asked| asked.index).collect();
+        assert_eq!(drawn.len(), 3);
+        assert!(drawn.iter().all(|&i| i < 10));
+    }
+
+    #[test]
+    fn session_sections() {
+        let quiz: Quiz = ";ordered: false\n---\n?intro\n+answer\n\n## Part 1: Minerals\n;value: 2\n\n?quartz\n+answer\n\n?feldspar\n+answer\n\n\
+            ;ordered: true\n?mica\n+answer\n\n## Part 2: Rocks\n;sample: 2\n?granite\n+answer\n\n?basalt\n+answer\n\n?shale\n+answer"
+            .parse().expect("quiz should parse");
+
+        for seed in 0..20 {
+            let mut session = QuizSession::with_seed(&quiz, seed);
+            let asked: Vec<usize> = session.questions.iter().map(|asked| asked.index).collect();
+
+            // questions are only shuffled within their sections, and part 2 only asks 2 of its questions
+            assert_eq!(asked.len(), 6);
+            assert_eq!(asked[0], 0);
+            assert_eq!(asked[1..3].iter().copied().min(), Some(1));
+            assert_eq!(asked[3], 3);
+            assert!(asked[4..].iter().all(|&i| i >= 4));
+
+            while let Some(question) = session.current_question() {
+                let answer = if question.title() == "quartz" { "wrong" } else { "answer" };
+                session.submit(Response::Text(answer.to_owned()));
+            }
+
+            assert_eq!(session.section_scores(), [
+                SectionScore { section: 0, score: 4.0, total_score: 6.0 },
+                SectionScore { section: 1, score: 2.0, total_score: 2.0 },
+            ]);
+        }
+    }
+
+    #[test]
+    fn session_hints() {
+        let quiz: Quiz = ";value: 2\n;hint-penalty: 0.3\n?question\n+answer\n~ one\n~ two\n~ three\n~ four\n\n?question2\n+answer\n~ hint".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+
+        assert_eq!(session.hint(), Some("one"));
+        assert_eq!(session.hint(), Some("two"));
+        assert_eq!(session.hints_used(), 2);
+
+        let feedback = session.submit(Response::Text("answer".to_owned())).expect("session should accept a response");
+        assert!(feedback.is_correct);
+        assert_eq!(feedback.hints_used, 2);
+        assert!((feedback.points - 0.8).abs() < 1e-6);
+
+        // hints are per-question
+        assert_eq!(session.hints_used(), 0);
+        assert_eq!(session.hint(), Some("hint"));
+        assert_eq!(session.hint(), None);
+        assert_eq!(session.hints_used(), 1);
+
+        // and can't take the value below zero
+        let quiz: Quiz = ";hint-penalty: 0.6\n?question\n+answer\n~ one\n~ two".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+        session.hint();
+        session.hint();
+
+        let feedback = session.submit(Response::Text("answer".to_owned())).expect("session should accept a response");
+        assert_eq!(feedback.points, 0.0);
+    }
+
+    #[test]
+    fn session_ask_inverse() {
+        let quiz: Quiz = ";ask-inverse: true\n?pona\n+good\n+simple\n-bad\n\n?ike\n+bad\n\n;ask-inverse: true\n?colour\n+re /^colou?r$/".parse().expect("quiz should parse");
+        let mut session = QuizSession::with_seed(&quiz, 1);
+
+        // only the first question can be inverted (patterns can't be asked)
+        assert_eq!(session.progress(), (0, 4));
+        assert_eq!(session.total_score(), 4.0);
+
+        for _ in 0..3 {
+            session.submit(Response::Text(String::new()));
+        }
+
+        let question = session.current_question().expect("session should have the inverse question");
+        assert!(question.is_inverse());
+        assert_eq!(question.title(), "good, simple");
+        assert_eq!(question.kind(), QuestionKind::Typed);
+
+        let feedback = session.submit(Response::Text("Pona ".to_owned())).expect("session should accept a response");
+        assert!(feedback.is_correct);
+        assert_eq!(feedback.correct_response, Response::Text("pona".to_owned()));
+        assert!(session.is_finished());
+    }
+
+    #[test]
+    fn session_justify() {
+        let quiz: Quiz = ";justified: true\n?question\n+answer\n-wrong\n\n?question2\n+answer".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+
+        assert!(!session.justify("nothing answered yet"));
+
+        session.submit(Response::Choices(vec![1]));
+        assert!(session.justify("it seemed right"));
+
+        session.submit(Response::Text("answer".to_owned()));
+        assert!(!session.justify("not asked for"));
+
+        assert_eq!(session.review_log(), [ReviewEntry {
+            question: 0,
+            title: "question".to_owned(),
+            answer: "wrong".to_owned(),
+            kind: ReviewKind::Justification("it seemed right".to_owned()),
+        }]);
+    }
+
+    #[test]
+    fn session_mark_correct() {
+        let quiz: Quiz = ";allow-review: true\n;value: 2\n;penalty: 1\n?question\n+answer\n\n?question2\n+answer".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+
+        session.submit(Response::Text("answr".to_owned()));
+        assert_eq!(session.score(), -1.0);
+
+        let feedback = session.mark_correct().expect("answer should be markable");
+        assert!(feedback.is_correct);
+        assert_eq!(feedback.points, 2.0);
+        assert_eq!(session.score(), 2.0);
+
+        // only once, and only for missed answers
+        assert_eq!(session.mark_correct(), None);
+        session.submit(Response::Text("answer".to_owned()));
+        assert_eq!(session.mark_correct(), None);
+
+        assert_eq!(session.review_log(), [ReviewEntry {
+            question: 0,
+            title: "question".to_owned(),
+            answer: "answr".to_owned(),
+            kind: ReviewKind::MarkedCorrect { correct_answer: "answer".to_owned() },
+        }]);
+
+        // overriding a missed answer while practicing takes it back out of practice
+        let quiz: Quiz = ";allow-review: true\n?question\n+answer\n\n?question2\n+answer".parse().expect("quiz should parse");
+        let mut session = QuizSession::with_seed(&quiz, 1).practice();
+
+        session.submit(Response::Text("answr".to_owned()));
+        assert_eq!(session.progress(), (1, 3));
+
+        let feedback = session.mark_correct().expect("answer should be markable");
+        assert!(!feedback.will_repeat);
+        assert_eq!(session.progress(), (1, 2));
+        assert!(session.mastery().is_empty());
+
+        session.submit(Response::Text("answer".to_owned()));
+        assert!(session.is_finished());
+
+        let resumed = QuizSession::resume(&quiz, &session.save()).expect("attempt should resume");
+        assert_eq!(resumed.save(), session.save());
+        assert!(resumed.mastery().is_empty());
+    }
+
+    #[test]
+    fn session_typed_case_sensitivity() {
+        let quiz: Quiz = "?question\n+Answer\n\n;case-sensitive: true\n?question2\n+Answer".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+
+        let feedback = session.submit(Response::Text("answer ".to_owned())).expect("session should accept a response");
+        assert!(feedback.is_correct);
+
+        let feedback = session.submit(Response::Text("answer".to_owned())).expect("session should accept a response");
+        assert!(!feedback.is_correct);
+        assert_eq!(feedback.correct_response, Response::Text("Answer".to_owned()));
+    }
+
+    #[test]
+    fn session_unordered_answers_keep_grading() {
+        let quiz: Quiz = ";ordered-answers: false\n?question\n+answer\n-a\n-b\n-c\n-d".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+
+        let question = session.current_question().expect("session should have a question");
+        let correct = question.options().iter().position(|&o| o == "answer").expect("the answer should be an option");
+
+        let feedback = session.submit(Response::Choices(vec![correct])).expect("session should accept a response");
+        assert!(feedback.is_correct);
+        assert_eq!(feedback.correct_response, Response::Choices(vec![correct]));
+    }
+
+    fn asked_order(session: &QuizSession) -> Vec<(usize, Vec<usize>)> {
+        session.questions.iter().map(|q| (q.index, q.answer_order.clone())).collect()
+    }
+
+    #[test]
+    fn session_seed_replays_order() {
+        let quiz: Quiz = ";ordered: false\n;ordered-answers: false\n---\n?q1\n+a\n-b\n-c\n\n?q2\n+a\n-b\n-c\n\n?q3\n+a\n-b\n-c\n\n?q4\n+a\n-b\n-c"
+            .parse().expect("quiz should parse");
+
+        let a = QuizSession::with_seed(&quiz, 1234);
+        let b = QuizSession::with_seed(&quiz, 1234);
+
+        assert_eq!(a.seed(), Some(1234));
+        assert_eq!(asked_order(&a), asked_order(&b));
+    }
+
+    #[test]
+    fn session_seed_from_config() {
+        let quiz: Quiz = ";seed: 99\n;ordered: false\n---\n?q1\n+a\n\n?q2\n+a\n\n?q3\n+a".parse().expect("quiz should parse");
+
+        let session = QuizSession::new(&quiz);
+        assert_eq!(session.seed(), Some(99));
+        assert_eq!(asked_order(&session), asked_order(&QuizSession::with_seed(&quiz, 99)));
+    }
+
+    #[test]
+    fn partial_credit_scoring() {
+        let correct = [0, 1];
+        let cases: &[(Scoring, &[usize], f32)] = &[
+            (Scoring::AllOrNothing, &[0, 1], 1.0),
+            (Scoring::AllOrNothing, &[0], 0.0),
+            (Scoring::Proportional, &[0, 1], 1.0),
+            (Scoring::Proportional, &[0], 0.75),
+            (Scoring::Proportional, &[0, 2], 0.5),
+            (Scoring::Proportional, &[0, 2, 3], 0.25),
+            (Scoring::Proportional, &[2, 3], 0.0),
+            (Scoring::Proportional, &[2], 0.0),
+            (Scoring::Proportional, &[], 0.0),
+            (Scoring::RightMinusWrong, &[0, 1], 1.0),
+            (Scoring::RightMinusWrong, &[0], 0.5),
+            (Scoring::RightMinusWrong, &[0, 2], 0.0),
+            (Scoring::RightMinusWrong, &[0, 2, 3], 0.0),
+        ];
+
+        for &(scoring, user_answers, expected) in cases {
+            assert_eq!(AskedQuestion::partial_credit(scoring, user_answers, &correct, 4), expected, "{scoring} with {user_answers:?}");
+        }
+    }
+
+    #[test]
+    fn partial_credit_only_for_multiple_answers() {
+        let quiz: Quiz = ";scoring: proportional\n;value: 4\n---\n?question\n+a\n-b\n-c\n-d\n\n?question2\n+a\n+b\n-c\n-d".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+
+        let feedback = session.submit(Response::Choices(vec![1])).expect("session should accept a response");
+        assert_eq!(feedback.points, 0.0);
+
+        let feedback = session.submit(Response::Choices(vec![0])).expect("session should accept a response");
+        assert!(!feedback.is_correct);
+        assert_eq!(feedback.points, 3.0);
+        assert_eq!(session.score(), 3.0);
+    }
+
+    #[test]
+    fn penalty_not_for_skipped() {
+        let quiz: Quiz = ";penalty: 0.25\n;scoring: proportional\n---\n?question\n+a\n+b\n-c\n\n?question2\n+a\n+b\n-c\n\n?question3\n+a".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+
+        let feedback = session.submit(Response::Choices(vec![])).expect("session should accept a response");
+        assert!(feedback.skipped);
+        assert_eq!(feedback.points, 0.0);
+
+        let feedback = session.submit(Response::Choices(vec![2])).expect("session should accept a response");
+        assert!(!feedback.skipped);
+        assert_eq!(feedback.points, -0.25);
+
+        let feedback = session.submit(Response::Text("  ".to_owned())).expect("session should accept a response");
+        assert!(feedback.skipped);
+        assert_eq!(feedback.points, 0.0);
+
+        assert_eq!(session.score(), -0.25);
+    }
+
+    #[test]
+    fn typed_multiple_spellings() {
+        let quiz: Quiz = ";typed: true\n?abbreviation?\n+USA\n+United States\n-Canada".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+
+        let question = session.current_question().expect("session should have a question");
+        assert_eq!(question.kind(), QuestionKind::Typed);
+        assert!(question.options().is_empty());
+
+        let feedback = session.submit(Response::Text("united states".to_owned())).expect("session should accept a response");
+        assert!(feedback.is_correct);
+        assert_eq!(feedback.matched_answer, Some(1));
+        assert_eq!(feedback.correct_response, Response::Text("USA".to_owned()));
+
+        let mut session = QuizSession::new(&quiz);
+        let feedback = session.submit(Response::Text("canada".to_owned())).expect("session should accept a response");
+        assert!(!feedback.is_correct);
+        assert_eq!(feedback.matched_answer, Some(2));
+
+        let mut session = QuizSession::new(&quiz);
+        let feedback = session.submit(Response::Text("mexico".to_owned())).expect("session should accept a response");
+        assert!(!feedback.is_correct);
+        assert_eq!(feedback.matched_answer, None);
+    }
+
+    #[test]
+    fn numeric_answers() {
+        let quiz: Quiz = "?g?\n+= 9.81 ± 0.05 m/s^2\n\n;numeric-tolerance: 1%\n?thousand?\n+= 1000\n-zero".parse().expect("quiz should parse");
+
+        let cases = [
+            ("9.8 m/s^2", "1e3", 2.0),
+            ("9.81", "1010", 2.0),
+            ("9.81 kg", "1011", 0.0),
+        ];
+
+        for (first, second, score) in cases {
+            let mut session = QuizSession::new(&quiz);
+
+            let question = session.current_question().expect("session should have a question");
+            assert_eq!(question.kind(), QuestionKind::Typed);
+
+            session.submit(Response::Text(first.to_owned()));
+            session.submit(Response::Text(second.to_owned()));
+            assert_eq!(session.score(), score, "{first}, {second}");
+        }
+    }
+
+    #[test]
+    fn regex_answers() {
+        let quiz: Quiz = "?spell it\n+re /^colou?r$/\n\n;case-sensitive: true\n?spell it\n+re /^colou?r$/\n+color".parse().expect("quiz should parse");
+
+        let mut session = QuizSession::new(&quiz);
+        let feedback = session.submit(Response::Text("COLOUR".to_owned())).expect("session should accept a response");
+        assert!(feedback.is_correct);
+        assert_eq!(feedback.correct_response, Response::Text("/^colou?r$/".to_owned()));
+
+        let question = session.current_question().expect("session should have a question");
+        assert_eq!(question.kind(), QuestionKind::Typed);
+
+        let feedback = session.submit(Response::Text("COLOUR".to_owned())).expect("session should accept a response");
+        assert!(!feedback.is_correct);
+        assert_eq!(feedback.correct_response, Response::Text("color".to_owned()));
+    }
+
+    #[test]
+    fn fuzzy_answers() {
+        let quiz: Quiz = ";fuzzy: 1\n?hello\n+toki\n\n;fuzzy: 1\n;normalize: true\n?how are you\n+¿Qué tal?\n\n?good\n+pona".parse().expect("quiz should parse");
+
+        let mut session = QuizSession::new(&quiz);
+
+        let feedback = session.submit(Response::Text("tokk".to_owned())).expect("session should accept a response");
+        assert!(feedback.is_correct);
+        assert!(feedback.accepted_with_typo);
+        assert_eq!(feedback.matched_answer, Some(0));
+
+        let feedback = session.submit(Response::Text("que  tal".to_owned())).expect("session should accept a response");
+        assert!(feedback.is_correct);
+        assert!(!feedback.accepted_with_typo);
+
+        let feedback = session.submit(Response::Text("pon".to_owned())).expect("session should accept a response");
+        assert!(!feedback.is_correct);
+
+        let mut session = QuizSession::new(&quiz);
+        let feedback = session.submit(Response::Text("tk".to_owned())).expect("session should accept a response");
+        assert!(!feedback.is_correct);
+        assert!(!feedback.accepted_with_typo);
+
+        // inverse questions are answered with the title, and wrong ones aren't typos either
+        let quiz: Quiz = ";fuzzy: 1\n;ask-inverse: true\n;ordered: true\n?pona\n+good".parse().expect("quiz should parse");
+        let mut session = QuizSession::new(&quiz);
+        session.submit(Response::Text("good".to_owned()));
+
+        let feedback = session.submit(Response::Text("pono".to_owned())).expect("session should accept a response");
+        assert!(feedback.is_correct && feedback.accepted_with_typo);
+
+        let mut session = QuizSession::new(&quiz);
+        session.submit(Response::Text("good".to_owned()));
+
+        let feedback = session.submit(Response::Text("ike".to_owned())).expect("session should accept a response");
+        assert!(!feedback.is_correct);
+        assert!(!feedback.accepted_with_typo);
+    }
+}
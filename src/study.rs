@@ -0,0 +1,298 @@
+//! Spaced-repetition study (`quiz-app study`), for using quizzes as flashcard decks.
+//!
+//! Every question gets an SM-2-style review schedule: answering it correctly pushes its next
+//! review further out (by its ease), and missing it brings it back the next day. Each study
+//! session only asks the questions that are due. Schedules are kept in a JSON Lines file, keyed by
+//! the quiz's path and the question's `Question::id`.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::Quiz;
+use crate::json::Json;
+use crate::results::{QuestionResult, QuizResult};
+
+/// Seconds in a day.
+pub const DAY: u64 = 24 * 60 * 60;
+
+/// When a question should next be reviewed, and how quickly its reviews spread out.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReviewState {
+    /// How much the interval grows after each correct review (never below 1.3)
+    pub ease: f32,
+    /// Days between the last review and the next one
+    pub interval: u32,
+    /// Correct reviews in a row
+    pub repetitions: u32,
+    /// When the question is due, in seconds since the unix epoch
+    pub due: u64,
+}
+
+impl Default for ReviewState {
+    /// A question that's never been reviewed (it's due right away).
+    fn default() -> Self {
+        Self { ease: 2.5, interval: 0, repetitions: 0, due: 0 }
+    }
+}
+
+impl ReviewState {
+    pub fn is_due(&self, now: u64) -> bool {
+        self.due <= now
+    }
+
+    /// Reschedule the question after a review at `now` that went as well as `quality` (from 0,
+    /// a blank, to 5, perfect; 3 and up is a pass).
+    pub fn review(&mut self, quality: u8, now: u64) {
+        let quality = quality.min(5);
+
+        if quality >= 3 {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (self.interval as f32 * self.ease).round() as u32,
+            };
+            self.repetitions += 1;
+        } else {
+            self.interval = 1;
+            self.repetitions = 0;
+        }
+
+        let miss = f32::from(5 - quality);
+        self.ease = (self.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(1.3);
+        self.due = now + u64::from(self.interval) * DAY;
+    }
+}
+
+/// How well a question went, on SM-2's 0-5 scale, going by how it was graded: 4 for a correct
+/// answer, 3 if it took hints, 2 for partial credit, 1 for a wrong answer, and 0 if skipped.
+pub fn quality(result: &QuestionResult) -> u8 {
+    match result {
+        QuestionResult { skipped: true, .. } => 0,
+        QuestionResult { is_correct: true, hints_used: 0, .. } => 4,
+        QuestionResult { is_correct: true, .. } => 3,
+        QuestionResult { points, .. } if *points > 0.0 => 2,
+        _ => 1,
+    }
+}
+
+/// The review schedules of every question studied.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StudyStore {
+    cards: Vec<Card>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Card {
+    /// The path of the quiz's file
+    quiz: String,
+    /// The question's `Question::id`
+    question: String,
+    state: ReviewState,
+}
+
+impl Card {
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("quiz".to_owned(), Json::string(&self.quiz)),
+            ("question".to_owned(), Json::string(&self.question)),
+            ("ease".to_owned(), Json::number(self.state.ease)),
+            ("interval".to_owned(), Json::number(self.state.interval)),
+            ("repetitions".to_owned(), Json::number(self.state.repetitions)),
+            ("due".to_owned(), Json::number(self.state.due)),
+        ])
+    }
+
+    fn from_json(json: &Json) -> Option<Self> {
+        Some(Self {
+            quiz: json.get("quiz")?.as_str()?.to_owned(),
+            question: json.get("question")?.as_str()?.to_owned(),
+            state: ReviewState {
+                ease: json.get("ease")?.as_number()?,
+                interval: json.get("interval")?.as_number()?,
+                repetitions: json.get("repetitions")?.as_number()?,
+                due: json.get("due")?.as_number()?,
+            },
+        })
+    }
+}
+
+impl StudyStore {
+    /// Where schedules are kept: `study.jsonl` next to the history (see `history::default_path`).
+    pub fn default_path() -> Option<PathBuf> {
+        Some(crate::history::data_dir()?.join("study.jsonl"))
+    }
+
+    /// Read the schedules at `path` (there are none if it doesn't exist yet).
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let store = match fs::read_to_string(path) {
+            Ok(store) => store,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            cards: store.lines()
+                .filter_map(|line| line.parse().ok())
+                .filter_map(|json| Card::from_json(&json))
+                .collect(),
+        })
+    }
+
+    /// Write the schedules to `path`, replacing what was there.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut store = Vec::new();
+        for card in &self.cards {
+            writeln!(store, "{0}", card.to_json())?;
+        }
+
+        // write everything first so a crash can't leave the schedules half-written
+        let temp_path = path.with_extension("jsonl.tmp");
+        fs::write(&temp_path, store)?;
+        fs::rename(temp_path, path)
+    }
+
+    /// The review schedule of the question with `question_id` in the quiz at `quiz`.
+    pub fn state(&self, quiz: &str, question_id: &str) -> ReviewState {
+        self.cards.iter()
+            .find(|card| card.quiz == quiz && card.question == question_id)
+            .map_or_else(ReviewState::default, |card| card.state)
+    }
+
+    /// Reschedule a question after a review (see `ReviewState::review`).
+    pub fn review(&mut self, quiz: &str, question_id: &str, quality: u8, now: u64) -> ReviewState {
+        let i = match self.cards.iter().position(|card| card.quiz == quiz && card.question == question_id) {
+            Some(i) => i,
+            None => {
+                self.cards.push(Card { quiz: quiz.to_owned(), question: question_id.to_owned(), state: ReviewState::default() });
+                self.cards.len() - 1
+            },
+        };
+
+        self.cards[i].state.review(quality, now);
+        self.cards[i].state
+    }
+
+    /// Reschedule every question answered in `result`, an attempt at `quiz` (whose file is at
+    /// `quiz_path`).
+    ///
+    /// A question that was asked more than once (see the `ask_inverse` option) is only as good as
+    /// its worst answer.
+    pub fn review_results(&mut self, quiz_path: &str, quiz: &Quiz, result: &QuizResult, now: u64) {
+        let mut reviewed: Vec<(usize, u8)> = vec![];
+
+        for question in &result.questions {
+            let quality = quality(question);
+
+            match reviewed.iter_mut().find(|(index, _)| *index == question.index) {
+                Some((_, worst)) => *worst = (*worst).min(quality),
+                None => reviewed.push((question.index, quality)),
+            }
+        }
+
+        for (index, quality) in reviewed {
+            if let Some(question) = quiz.questions.get(index) {
+                self.review(quiz_path, &question.id(), quality, now);
+            }
+        }
+    }
+
+    /// When the next question in `quiz` (whose file is at `quiz_path`) is due.
+    pub fn next_due(&self, quiz_path: &str, quiz: &Quiz) -> Option<u64> {
+        quiz.questions.iter()
+            .map(|question| self.state(quiz_path, &question.id()).due)
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QuizSession;
+    use crate::Response;
+
+    #[test]
+    fn sm2_schedule() {
+        let mut state = ReviewState::default();
+        assert!(state.is_due(0));
+
+        state.review(4, 0);
+        assert_eq!((state.interval, state.repetitions, state.due), (1, 1, DAY));
+        assert!(!state.is_due(DAY - 1));
+        assert!(state.is_due(DAY));
+
+        state.review(4, DAY);
+        assert_eq!((state.interval, state.due), (6, 7 * DAY));
+
+        state.review(5, 7 * DAY);
+        assert_eq!(state.interval, 15);
+        assert!((state.ease - 2.6).abs() < 1e-6);
+
+        // a miss starts the question over (and makes it harder)
+        state.review(1, 22 * DAY);
+        assert_eq!((state.interval, state.repetitions, state.due), (1, 0, 23 * DAY));
+        assert!((state.ease - 2.06).abs() < 1e-6);
+
+        for _ in 0..10 {
+            state.review(0, 0);
+        }
+        assert_eq!(state.ease, 1.3);
+    }
+
+    #[test]
+    fn study_session() {
+        let quiz: Quiz = "?question\n+answer\n\n;ask-inverse: true\n?question2\n+answer2\n\n?question3\n+answer3\n~ hint"
+            .parse().expect("quiz should parse");
+        let mut store = StudyStore::default();
+
+        // everything is due at first
+        let mut session = QuizSession::new(&quiz).only(|_, question| store.state("rocks.qz", &question.id()).is_due(0));
+        while let Some(asked) = session.current_question().cloned() {
+            if asked.title() == "question3" {
+                session.hint();
+            }
+
+            let answer = match asked.title() {
+                "question" => "wrong",
+                "question2" => "answer2",
+                "question3" => "answer3",
+                _ => "wrong", // the inverse of question2
+            };
+            session.submit(Response::Text(answer.to_owned()));
+        }
+
+        store.review_results("rocks.qz", &quiz, &session.result(), 0);
+
+        let state = |i: usize| store.state("rocks.qz", &quiz.questions[i].id());
+        assert_eq!((state(0).repetitions, state(0).due), (0, DAY));
+        assert_eq!((state(1).repetitions, state(1).due), (0, DAY)); // its inverse was missed
+        assert_eq!((state(2).repetitions, state(2).due), (1, DAY));
+        assert!((state(2).ease - 2.36).abs() < 1e-6); // hints make it harder
+        assert_eq!(store.next_due("rocks.qz", &quiz), Some(DAY));
+
+        // nothing is due until tomorrow, and other quizzes aren't affected
+        assert_eq!(QuizSession::new(&quiz).only(|_, question| store.state("rocks.qz", &question.id()).is_due(DAY - 1)).progress(), (0, 0));
+        assert!(store.state("minerals.qz", &quiz.questions[0].id()).is_due(0));
+    }
+
+    #[test]
+    fn store_round_trips() {
+        let dir = std::env::temp_dir().join(format!("quiz-app-study-test-{0}", std::process::id()));
+        let path = dir.join("study.jsonl");
+
+        let mut store = StudyStore::default();
+        store.review("rocks.qz", "0123", 4, 100);
+        store.review("rocks.qz", "4567", 1, 100);
+        store.review("rocks.qz", "0123", 4, DAY + 100);
+        store.write(&path).expect("store should be written");
+
+        assert_eq!(StudyStore::read(&path).expect("store should be read"), store);
+        assert_eq!(StudyStore::read(&dir.join("missing.jsonl")).expect("missing store is empty"), StudyStore::default());
+
+        fs::remove_dir_all(dir).expect("test store should be removed");
+    }
+}